@@ -0,0 +1,303 @@
+use crate::batch::run_limited_async;
+use crate::cache::{Cache, load_cached, require_online, store_fetched};
+use crate::client::{CONTENT_TYPE_JSON, Config, Response, ResponseInfo, USER_AGENT, parse_crts};
+use crate::crt::Crt;
+use crate::dedupe::dedupe_crts;
+use crate::error::{Error, Result};
+use crate::query::Query;
+use crate::retry::{RetryEvent, RetryHook};
+use crate::transport::{Transport, execute_with_retry};
+use reqwest::Client;
+use tokio::task::JoinError;
+use std::time::Duration;
+
+/// crt.sh API の非同期クライアント（tokio 上で動作）
+pub struct AsyncCrtShClient {
+    config: Config,
+    client: Client,
+    retry_hook: Option<RetryHook>,
+    cache: Option<Cache>,
+}
+
+impl AsyncCrtShClient {
+    /// 設定からクライアントを作成
+    pub fn new(config: Config) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(config.timeout))
+            .user_agent(USER_AGENT)
+            .build()?;
+        let cache = Cache::from_config(&config);
+
+        Ok(Self {
+            config,
+            client,
+            retry_hook: None,
+            cache,
+        })
+    }
+
+    /// リトライ時のフックを設定
+    pub fn on_retry(mut self, hook: impl Fn(&RetryEvent) + Send + Sync + 'static) -> Self {
+        self.retry_hook = Some(Box::new(hook));
+        self
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// クエリで検索して証明書の一覧を取得（設定の重複統合・絞り込み条件を適用）
+    pub async fn search(&self, query: &Query) -> Result<Vec<Crt>> {
+        let response = self.fetch(&query.to_url()).await?;
+
+        let mut crts = parse_crts(&response.body)?;
+        if self.config.dedupe {
+            crts = dedupe_crts(crts);
+        }
+
+        Ok(self.config.filters.apply(crts))
+    }
+
+    /// 複数の URL を取得（同時実行数・リクエスト間隔は設定に従い、結果は入力と同じ順）
+    ///
+    /// キャッシュから取得できる URL はリクエスト間隔を空けずに返す。
+    pub async fn fetch_all(&self, urls: &[String]) -> Vec<Result<Response>> {
+        let interval = Duration::from_secs_f64(self.config.request_delay.max(0.0));
+
+        let cached = match self.load_all_cached(urls).await {
+            Ok(cached) => cached,
+            Err(e) => {
+                let message = e.to_string();
+                return urls.iter().map(|_| Err(Error::cache(&message))).collect();
+            }
+        };
+        let missing: Vec<&String> = urls
+            .iter()
+            .zip(&cached)
+            .filter(|(_, response)| response.is_none())
+            .map(|(url, _)| url)
+            .collect();
+
+        let mut fetched = run_limited_async(&missing, self.config.concurrency, interval, |url| {
+            self.fetch_uncached(url)
+        })
+        .await
+        .into_iter();
+
+        cached
+            .into_iter()
+            .filter_map(|response| response.map(Ok).or_else(|| fetched.next()))
+            .collect()
+    }
+
+    /// URL を取得してレスポンスをそのまま返す（キャッシュがあればキャッシュから返す）
+    pub async fn fetch(&self, url: &str) -> Result<Response> {
+        if let Some(response) = self.load_cached(url).await? {
+            return Ok(response);
+        }
+
+        self.fetch_uncached(url).await
+    }
+
+    /// キャッシュを確認済みの URL を取得して保存（オフラインの場合はエラー）
+    async fn fetch_uncached(&self, url: &str) -> Result<Response> {
+        require_online(self.cache.as_ref(), self.config.cache_mode, url)?;
+
+        let request = self.client.get(url).build()?;
+
+        let response =
+            execute_with_retry(&self.client, request, &self.config, self.retry_hook.as_ref())
+                .await?;
+
+        self.store_fetched(url, response).await
+    }
+
+    /// JSON を POST してレスポンスを返す（Webhook の送信などに使用、リトライ設定に従う）
+    pub async fn post_json(&self, url: &str, body: String) -> Result<Response> {
+        let request = self
+            .client
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, CONTENT_TYPE_JSON)
+            .body(body)
+            .build()?;
+
+        execute_with_retry(&self.client, request, &self.config, self.retry_hook.as_ref()).await
+    }
+
+    /// 取得前にキャッシュを確認（ファイルの読み込みはブロッキング用のスレッドで実行）
+    async fn load_cached(&self, url: &str) -> Result<Option<Response>> {
+        let mode = self.config.cache_mode;
+        let Some(cache) = self.cache.clone() else {
+            return load_cached(None, mode, url);
+        };
+
+        let url = url.to_string();
+        tokio::task::spawn_blocking(move || load_cached(Some(&cache), mode, &url))
+            .await
+            .map_err(Error::cache)?
+    }
+
+    /// 複数の URL のキャッシュを確認（キャッシュがない URL は None）
+    async fn load_all_cached(&self, urls: &[String]) -> std::result::Result<Vec<Option<Response>>, JoinError> {
+        let Some(cache) = self.cache.clone() else {
+            return Ok(urls.iter().map(|_| None).collect());
+        };
+
+        let urls = urls.to_vec();
+        tokio::task::spawn_blocking(move || urls.iter().map(|url| cache.load(url)).collect())
+            .await
+    }
+
+    /// 取得したレスポンスを保存して返す（上限を超えたエントリの削除を含め、ブロッキング用のスレッドで実行）
+    async fn store_fetched(&self, url: &str, response: Response) -> Result<Response> {
+        let Some(cache) = self.cache.clone() else {
+            return Ok(response);
+        };
+
+        let url = url.to_string();
+        tokio::task::spawn_blocking(move || {
+            store_fetched(Some(&cache), &url, &response);
+            response
+        })
+        .await
+        .map_err(Error::cache)
+    }
+}
+
+impl Transport for Client {
+    type Request = reqwest::Request;
+    type Response = reqwest::Response;
+
+    fn try_clone(request: &Self::Request) -> Option<Self::Request> {
+        request.try_clone()
+    }
+
+    fn execute(
+        &self,
+        request: Self::Request,
+    ) -> impl Future<Output = reqwest::Result<Self::Response>> {
+        Client::execute(self, request)
+    }
+
+    fn info(response: &Self::Response) -> ResponseInfo {
+        ResponseInfo::new(response.status(), response.version(), response.headers().clone())
+    }
+
+    fn text(response: Self::Response) -> impl Future<Output = reqwest::Result<String>> {
+        response.text()
+    }
+
+    fn sleep(delay: Duration) -> impl Future<Output = ()> {
+        tokio::time::sleep(delay)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::thread;
+
+    const CRTS_BODY: &str = r#"[{"id":1,"common_name":"example.com","entry_timestamp":null,"issuer_ca_id":1,"issuer_name":"C=US, O=Example CA","name_value":"example.com","not_after":"2026-03-16T20:59:52","not_before":"2025-12-16T00:00:00","result_count":1,"serial_number":"01"}]"#;
+
+    /// ステータスとボディから HTTP レスポンスを作成
+    fn http(status: &str, body: &str) -> String {
+        format!(
+            "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            body.len(),
+            body
+        )
+    }
+
+    /// 受け付けた順に responses を返すローカルの HTTP サーバー
+    fn serve(responses: Vec<String>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+
+        thread::spawn(move || {
+            for (stream, response) in listener.incoming().zip(responses) {
+                let mut stream = stream.unwrap();
+                let mut request = [0; 4096];
+                let _ = stream.read(&mut request);
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+
+        url
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    fn config(retry: u32) -> Config {
+        Config {
+            retry,
+            retry_delay: 0.0,
+            request_delay: 0.0,
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn fetch_retries_server_errors_and_notifies_hook() {
+        let url = serve(vec![
+            http("503 Service Unavailable", ""),
+            http("200 OK", CRTS_BODY),
+        ]);
+        let retries = Arc::new(AtomicU32::new(0));
+        let counter = Arc::clone(&retries);
+        let client = AsyncCrtShClient::new(config(1))
+            .unwrap()
+            .on_retry(move |_| {
+                counter.fetch_add(1, Ordering::SeqCst);
+            });
+
+        let response = block_on(client.fetch(&url)).unwrap();
+
+        assert_eq!(response.body, CRTS_BODY);
+        assert_eq!(retries.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn fetch_returns_http_status_error_after_retries() {
+        let url = serve(vec![http("500 Internal Server Error", "down"); 2]);
+        let client = AsyncCrtShClient::new(config(1)).unwrap();
+
+        let error = block_on(client.fetch(&url)).unwrap_err();
+
+        assert!(matches!(error, Error::HttpStatus { status, .. } if status.as_u16() == 500));
+    }
+
+    #[test]
+    fn fetch_all_keeps_input_order_and_per_url_errors() {
+        let ok = serve(vec![http("200 OK", CRTS_BODY)]);
+        let not_found = serve(vec![http("404 Not Found", "")]);
+        let client = AsyncCrtShClient::new(config(0)).unwrap();
+
+        let responses = block_on(client.fetch_all(&[not_found, ok]));
+
+        assert!(matches!(responses[0], Err(Error::HttpStatus { .. })));
+        assert_eq!(responses[1].as_ref().unwrap().body, CRTS_BODY);
+    }
+
+    #[test]
+    fn futures_can_be_spawned() {
+        fn assert_send<T: Send>(_: &T) {}
+
+        let client = AsyncCrtShClient::new(config(0)).unwrap();
+        let urls = [String::new()];
+
+        assert_send(&client.fetch(""));
+        assert_send(&client.fetch_all(&urls));
+        assert_send(&client.post_json("", String::new()));
+    }
+}
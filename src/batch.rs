@@ -0,0 +1,291 @@
+#[cfg(feature = "async")]
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, PoisonError};
+#[cfg(feature = "async")]
+use std::task::Poll;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// 非同期の処理を順に実行するワーカー（完了した処理の入力位置と結果を返す）
+#[cfg(feature = "async")]
+type AsyncWorker<'a, R> = Pin<Box<dyn Future<Output = Vec<(usize, R)>> + Send + 'a>>;
+
+/// 同時実行数と開始間隔を制限して処理を実行（結果は入力と同じ順）
+pub(crate) fn run_limited<T, R, F>(
+    items: &[T],
+    concurrency: usize,
+    interval: Duration,
+    task: F,
+) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let workers = concurrency.clamp(1, items.len().max(1));
+    let next_index = AtomicUsize::new(0);
+    let next_start = Mutex::new(Instant::now());
+
+    let mut results: Vec<(usize, R)> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = Vec::new();
+                    loop {
+                        let index = next_index.fetch_add(1, Ordering::SeqCst);
+                        let Some(item) = items.get(index) else {
+                            break;
+                        };
+
+                        wait_for_turn(&next_start, interval);
+                        done.push((index, task(item)));
+                    }
+                    done
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .flat_map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+            })
+            .collect()
+    });
+
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+/// 同時実行数と開始間隔を制限して非同期処理を実行（結果は入力と同じ順）
+///
+/// タスクは呼び出し元のタスク内で並行に実行する（tokio のランタイムにタスクを生成しない）。
+#[cfg(feature = "async")]
+pub(crate) async fn run_limited_async<'a, T, R, F, Fut>(
+    items: &'a [T],
+    concurrency: usize,
+    interval: Duration,
+    task: F,
+) -> Vec<R>
+where
+    T: Sync,
+    R: Send + 'a,
+    F: Fn(&'a T) -> Fut + Sync + 'a,
+    Fut: Future<Output = R> + Send + 'a,
+{
+    let workers = concurrency.clamp(1, items.len().max(1));
+    let next_index = AtomicUsize::new(0);
+    let next_start = Mutex::new(Instant::now());
+
+    let mut pending: Vec<AsyncWorker<'_, R>> = (0..workers)
+        .map(|_| {
+            let worker: AsyncWorker<'_, R> = Box::pin(async {
+                let mut done = Vec::new();
+                loop {
+                    let index = next_index.fetch_add(1, Ordering::SeqCst);
+                    let Some(item) = items.get(index) else {
+                        break;
+                    };
+
+                    let wait = reserve_turn(&next_start, interval);
+                    if !wait.is_zero() {
+                        tokio::time::sleep(wait).await;
+                    }
+                    done.push((index, task(item).await));
+                }
+                done
+            });
+            worker
+        })
+        .collect();
+
+    let mut results: Vec<(usize, R)> = Vec::new();
+    std::future::poll_fn(|cx| {
+        pending.retain_mut(|worker| match worker.as_mut().poll(cx) {
+            Poll::Ready(done) => {
+                results.extend(done);
+                false
+            }
+            Poll::Pending => true,
+        });
+
+        if pending.is_empty() {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    })
+    .await;
+
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+/// 前回の開始から interval が経過するまで待機
+fn wait_for_turn(next_start: &Mutex<Instant>, interval: Duration) {
+    let wait = reserve_turn(next_start, interval);
+
+    if !wait.is_zero() {
+        thread::sleep(wait);
+    }
+}
+
+/// 次の開始時刻を予約し、それまでの待機時間を返す
+fn reserve_turn(next_start: &Mutex<Instant>, interval: Duration) -> Duration {
+    let mut next_start = next_start.lock().unwrap_or_else(PoisonError::into_inner);
+    let now = Instant::now();
+    let start = (*next_start).max(now);
+    *next_start = start + interval;
+
+    start - now
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WORK: Duration = Duration::from_millis(20);
+    const INTERVAL: Duration = Duration::from_millis(30);
+
+    /// 同時に実行中の処理の数と、その最大値
+    #[derive(Default)]
+    struct Running {
+        current: AtomicUsize,
+        max: AtomicUsize,
+    }
+
+    impl Running {
+        fn enter(&self) {
+            let current = self.current.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max.fetch_max(current, Ordering::SeqCst);
+        }
+
+        fn leave(&self) {
+            self.current.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn never_runs_more_tasks_than_concurrency() {
+        let running = Running::default();
+        let items: Vec<usize> = (0..8).collect();
+
+        run_limited(&items, 3, Duration::ZERO, |_| {
+            running.enter();
+            thread::sleep(WORK);
+            running.leave();
+        });
+
+        let max = running.max.load(Ordering::SeqCst);
+        assert!((2..=3).contains(&max), "{max}");
+    }
+
+    #[test]
+    fn zero_concurrency_runs_tasks_one_at_a_time() {
+        let running = Running::default();
+        let items: Vec<usize> = (0..3).collect();
+
+        let results = run_limited(&items, 0, Duration::ZERO, |item| {
+            running.enter();
+            thread::sleep(WORK);
+            running.leave();
+            *item
+        });
+
+        assert_eq!(results, [0, 1, 2]);
+        assert_eq!(running.max.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn spaces_task_starts_by_interval() {
+        let items: Vec<usize> = (0..4).collect();
+        let begin = Instant::now();
+
+        let mut starts = run_limited(&items, 4, INTERVAL, |_| begin.elapsed());
+        starts.sort();
+
+        for (position, start) in starts.iter().enumerate() {
+            assert!(
+                *start >= INTERVAL * position as u32,
+                "{position}: {start:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn keeps_input_order_and_collects_each_error() {
+        let items = ["a", "fail-b", "c", "fail-d"];
+
+        let results = run_limited(&items, 2, Duration::ZERO, |item| {
+            // 後の入力ほど早く完了させて、完了順と入力順を入れ替える
+            thread::sleep(WORK / (items.iter().position(|i| i == item).unwrap() as u32 + 1));
+            match item.strip_prefix("fail-") {
+                Some(name) => Err(name.to_string()),
+                None => Ok(item.to_string()),
+            }
+        });
+
+        assert_eq!(
+            results,
+            [
+                Ok("a".to_string()),
+                Err("b".to_string()),
+                Ok("c".to_string()),
+                Err("d".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_input_returns_no_results() {
+        let results: Vec<()> = run_limited(&[] as &[usize], 4, INTERVAL, |_| ());
+
+        assert!(results.is_empty());
+    }
+
+    #[cfg(feature = "async")]
+    fn block_on<F: Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn async_limits_concurrency_and_spaces_starts() {
+        let running = Running::default();
+        let items: Vec<usize> = (0..4).collect();
+        let begin = Instant::now();
+
+        let results = block_on(run_limited_async(&items, 2, INTERVAL, |item| {
+            let running = &running;
+            async move {
+                running.enter();
+                let start = begin.elapsed();
+                tokio::time::sleep(WORK).await;
+                running.leave();
+                (*item, start)
+            }
+        }));
+
+        assert_eq!(
+            results.iter().map(|(item, _)| *item).collect::<Vec<_>>(),
+            items
+        );
+        assert!(running.max.load(Ordering::SeqCst) <= 2);
+
+        let mut starts: Vec<Duration> = results.iter().map(|(_, start)| *start).collect();
+        starts.sort();
+        for (position, start) in starts.iter().enumerate() {
+            assert!(
+                *start >= INTERVAL * position as u32,
+                "{position}: {start:?}"
+            );
+        }
+    }
+}
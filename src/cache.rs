@@ -0,0 +1,394 @@
+use crate::client::{Config, Response, ResponseInfo, TimingInfo};
+use crate::error::{Error, Result};
+use reqwest::Url;
+use reqwest::header::HeaderMap;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+// デフォルト値
+pub const DEFAULT_CACHE_TTL_SECS: u64 = 3600;
+pub const DEFAULT_CACHE_MAX_SIZE_MB: u64 = 100;
+
+// キャッシュの保存先
+const CACHE_DIR_NAME: &str = "rs-crtsh";
+const CACHE_HOME_ENV: &str = "XDG_CACHE_HOME";
+const HOME_ENV: &str = "HOME";
+const HOME_CACHE_DIR: &str = ".cache";
+const CACHE_FILE_EXTENSION: &str = "json";
+const CACHE_TEMP_EXTENSION: &str = "tmp";
+const CACHE_SIZE_FILE_NAME: &str = "size";
+
+// エラーメッセージ
+const ERROR_OFFLINE_CACHE_MISS: &str = "No cached response for {} (offline mode)";
+const ERROR_OFFLINE_NO_CACHE: &str = "Offline mode needs the response cache, but it is disabled or has no cache directory";
+
+// サイズの換算
+const BYTES_PER_MB: u64 = 1024 * 1024;
+
+// FNV-1a（キャッシュファイル名のハッシュ）
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// キャッシュの使い方
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CacheMode {
+    /// 有効期限内のキャッシュがあれば使用し、なければ取得して保存
+    #[default]
+    Use,
+    /// キャッシュを読まずに取得し、結果を保存
+    Refresh,
+    /// キャッシュを読み書きしない
+    Disabled,
+    /// キャッシュのみを使用（有効期限切れも使用し、ネットワークには接続しない）
+    Offline,
+}
+
+// キャッシュファイルの内容
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    url: String,
+    fetched_at: u64,
+    status: u16,
+    body: String,
+}
+
+/// URL ごとにレスポンスボディを保存するディスクキャッシュ
+#[derive(Debug, Clone)]
+pub struct Cache {
+    dir: PathBuf,
+    mode: CacheMode,
+    ttl: Duration,
+    max_size: u64,
+}
+
+impl Cache {
+    /// 設定からキャッシュを作成（無効な場合や保存先がない場合は None）
+    pub fn from_config(config: &Config) -> Option<Self> {
+        if config.cache_mode == CacheMode::Disabled {
+            return None;
+        }
+
+        Some(Self {
+            dir: config.cache_dir.clone()?,
+            mode: config.cache_mode,
+            ttl: config.cache_ttl,
+            max_size: config.cache_max_size.saturating_mul(BYTES_PER_MB),
+        })
+    }
+
+    /// キャッシュ済みのレスポンスを取得（期限切れ・読み込めない場合は None）
+    pub fn load(&self, url: &str) -> Option<Response> {
+        if self.mode == CacheMode::Refresh {
+            return None;
+        }
+
+        let key = cache_key(url);
+        let contents = fs::read_to_string(self.entry_path(&key)).ok()?;
+        let entry: CacheEntry = serde_json::from_str(&contents).ok()?;
+
+        if entry.url != key {
+            return None;
+        }
+
+        let age = unix_now().saturating_sub(entry.fetched_at);
+        if self.mode != CacheMode::Offline && age >= self.ttl.as_secs() {
+            return None;
+        }
+
+        Some(Response {
+            info: ResponseInfo::new(
+                reqwest::StatusCode::from_u16(entry.status).ok()?,
+                reqwest::Version::default(),
+                HeaderMap::new(),
+            ),
+            body: entry.body,
+            timing: TimingInfo::new(Duration::ZERO, Duration::ZERO, Duration::ZERO),
+            cache_age: Some(Duration::from_secs(age)),
+        })
+    }
+
+    /// レスポンスを保存し、合計サイズの上限を超えた古いエントリを削除
+    ///
+    /// 合計サイズは記録した値から見積もり、上限を超えた場合のみディレクトリを走査する。
+    pub fn store(&self, url: &str, response: &Response) -> Result<()> {
+        let key = cache_key(url);
+        let entry = CacheEntry {
+            url: key.clone(),
+            fetched_at: unix_now(),
+            status: response.info.status().as_u16(),
+            body: response.body.clone(),
+        };
+
+        let contents = serde_json::to_string(&entry).map_err(Error::cache)?;
+        let path = self.entry_path(&key);
+        let temp_path = path.with_extension(CACHE_TEMP_EXTENSION);
+        let previous_size = fs::metadata(&path).map_or(0, |metadata| metadata.len());
+
+        fs::create_dir_all(&self.dir)
+            .and_then(|_| fs::write(&temp_path, &contents))
+            .and_then(|_| fs::rename(&temp_path, &path))
+            .map_err(Error::cache)?;
+
+        let total_size = self
+            .recorded_size()
+            .map(|size| size.saturating_sub(previous_size) + contents.len() as u64);
+
+        match total_size {
+            Some(total_size) if total_size <= self.max_size => self.record_size(total_size),
+            _ => self.prune(),
+        }
+        .map_err(Error::cache)
+    }
+
+    /// 合計サイズが上限を超える場合は古い順に削除（最も新しいエントリは上限を超えても残す）
+    ///
+    /// 有効期限切れのエントリは `--offline` や `diff cache:` で使用するため、期限では削除しない。
+    pub fn prune(&self) -> io::Result<()> {
+        let mut entries = Vec::new();
+
+        for dir_entry in fs::read_dir(&self.dir)? {
+            let path = dir_entry?.path();
+            if path.extension().is_none_or(|extension| extension != CACHE_FILE_EXTENSION) {
+                continue;
+            }
+
+            let metadata = fs::metadata(&path)?;
+            entries.push((metadata.modified()?, metadata.len(), path));
+        }
+
+        entries.sort();
+
+        let mut total_size: u64 = entries.iter().map(|(_, size, _)| size).sum();
+        let newest = entries.pop();
+        for (_, size, path) in &entries {
+            if total_size <= self.max_size {
+                break;
+            }
+            remove_entry(path)?;
+            total_size -= size;
+        }
+
+        if newest.is_some() {
+            self.record_size(total_size)?;
+        }
+
+        Ok(())
+    }
+
+    /// 記録した合計サイズ（記録がない場合は None）
+    fn recorded_size(&self) -> Option<u64> {
+        fs::read_to_string(self.dir.join(CACHE_SIZE_FILE_NAME))
+            .ok()?
+            .trim()
+            .parse()
+            .ok()
+    }
+
+    /// 合計サイズを記録
+    fn record_size(&self, total_size: u64) -> io::Result<()> {
+        fs::write(self.dir.join(CACHE_SIZE_FILE_NAME), total_size.to_string())
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.dir
+            .join(format!("{:016x}", fnv1a(key.as_bytes())))
+            .with_extension(CACHE_FILE_EXTENSION)
+    }
+}
+
+/// 取得前にキャッシュを確認（取得が必要な場合は None、オフラインでキャッシュがない場合はエラー）
+pub(crate) fn load_cached(cache: Option<&Cache>, mode: CacheMode, url: &str) -> Result<Option<Response>> {
+    if let Some(response) = cache.and_then(|cache| cache.load(url)) {
+        return Ok(Some(response));
+    }
+
+    require_online(cache, mode, url)?;
+    Ok(None)
+}
+
+/// キャッシュにない URL をネットワークから取得できるか確認（オフラインの場合はエラー）
+pub(crate) fn require_online(cache: Option<&Cache>, mode: CacheMode, url: &str) -> Result<()> {
+    if mode != CacheMode::Offline {
+        return Ok(());
+    }
+
+    match cache {
+        Some(_) => Err(Error::Cache(ERROR_OFFLINE_CACHE_MISS.replace("{}", url))),
+        None => Err(Error::Cache(ERROR_OFFLINE_NO_CACHE.to_string())),
+    }
+}
+
+/// 取得したレスポンスを保存（保存に失敗しても取得結果は返すため、エラーは無視する）
+pub(crate) fn store_fetched(cache: Option<&Cache>, url: &str, response: &Response) {
+    if let Some(cache) = cache {
+        let _ = cache.store(url, response);
+    }
+}
+
+/// デフォルトのキャッシュディレクトリ（$XDG_CACHE_HOME/rs-crtsh または ~/.cache/rs-crtsh）
+pub fn default_cache_dir() -> Option<PathBuf> {
+    let cache_home = env::var_os(CACHE_HOME_ENV)
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os(HOME_ENV).map(|home| PathBuf::from(home).join(HOME_CACHE_DIR)))?;
+
+    Some(cache_home.join(CACHE_DIR_NAME))
+}
+
+/// URL を正規化したキャッシュキー（フラグメントを除き、クエリパラメータを並べ替える）
+pub fn cache_key(url: &str) -> String {
+    let Ok(mut parsed) = Url::parse(url.trim()) else {
+        return url.trim().to_string();
+    };
+
+    let mut pairs: Vec<(String, String)> = parsed.query_pairs().into_owned().collect();
+    pairs.sort();
+
+    parsed.set_fragment(None);
+    if pairs.is_empty() {
+        parsed.set_query(None);
+    } else {
+        parsed.query_pairs_mut().clear().extend_pairs(pairs);
+    }
+
+    parsed.into()
+}
+
+/// エントリを削除（他のプロセスが削除済みの場合は無視）
+fn remove_entry(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// 現在の UNIX 時刻（秒）
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+/// FNV-1a ハッシュ（Rust のバージョンによらず同じ値になるファイル名に使用）
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(FNV_OFFSET_BASIS, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process;
+
+    fn temp_cache(name: &str, mode: CacheMode, ttl: Duration, max_size: u64) -> Cache {
+        let dir = env::temp_dir().join(format!("rs-crtsh-cache-{}-{}", name, process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        Cache {
+            dir,
+            mode,
+            ttl,
+            max_size,
+        }
+    }
+
+    fn response(body: &str) -> Response {
+        Response::received(
+            ResponseInfo::new(
+                reqwest::StatusCode::OK,
+                reqwest::Version::default(),
+                HeaderMap::new(),
+            ),
+            body.to_string(),
+            TimingInfo::new(Duration::ZERO, Duration::ZERO, Duration::ZERO),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn keeps_expired_entries_for_offline_use() {
+        let cache = temp_cache("expired", CacheMode::Use, Duration::ZERO, u64::MAX);
+        cache.store("https://crt.sh/?q=a", &response("[]")).unwrap();
+
+        assert!(cache.load("https://crt.sh/?q=a").is_none());
+
+        let offline = Cache {
+            mode: CacheMode::Offline,
+            ..cache.clone()
+        };
+        assert_eq!(offline.load("https://crt.sh/?q=a").unwrap().body, "[]");
+
+        fs::remove_dir_all(&cache.dir).unwrap();
+    }
+
+    #[test]
+    fn prunes_older_entries_but_keeps_newest_over_size_limit() {
+        let cache = temp_cache("size", CacheMode::Use, Duration::MAX, 1);
+        cache.store("https://crt.sh/?q=a", &response("[]")).unwrap();
+
+        assert_eq!(cache.load("https://crt.sh/?q=a").unwrap().body, "[]");
+
+        cache.store("https://crt.sh/?q=b", &response("[]")).unwrap();
+
+        assert!(cache.load("https://crt.sh/?q=a").is_none());
+        assert_eq!(cache.load("https://crt.sh/?q=b").unwrap().body, "[]");
+
+        fs::remove_dir_all(&cache.dir).unwrap();
+    }
+
+    #[test]
+    fn records_total_size_of_stored_entries() {
+        let cache = temp_cache("record", CacheMode::Use, Duration::MAX, u64::MAX);
+        cache.store("https://crt.sh/?q=a", &response("[]")).unwrap();
+        cache
+            .store("https://crt.sh/?q=b", &response("[1]"))
+            .unwrap();
+        cache
+            .store("https://crt.sh/?q=a", &response("[1, 2]"))
+            .unwrap();
+
+        let entries_size: u64 = ["https://crt.sh/?q=a", "https://crt.sh/?q=b"]
+            .iter()
+            .map(|url| {
+                fs::metadata(cache.entry_path(&cache_key(url)))
+                    .unwrap()
+                    .len()
+            })
+            .sum();
+        assert_eq!(cache.recorded_size(), Some(entries_size));
+
+        fs::remove_dir_all(&cache.dir).unwrap();
+    }
+
+    #[test]
+    fn offline_miss_reports_missing_entry_or_disabled_cache() {
+        let cache = temp_cache("offline", CacheMode::Offline, Duration::MAX, u64::MAX);
+        let url = "https://crt.sh/?q=a";
+
+        let Err(Error::Cache(miss)) = load_cached(Some(&cache), CacheMode::Offline, url) else {
+            panic!("expected a cache miss");
+        };
+        assert!(miss.contains(url));
+
+        let Err(Error::Cache(disabled)) = load_cached(None, CacheMode::Offline, url) else {
+            panic!("expected a disabled cache");
+        };
+        assert!(disabled.contains("disabled"));
+
+        assert!(load_cached(None, CacheMode::Use, url).unwrap().is_none());
+    }
+
+    #[test]
+    fn normalizes_cache_key() {
+        assert_eq!(
+            cache_key("https://crt.sh/?q=a&output=json#top"),
+            cache_key("https://crt.sh/?output=json&q=a")
+        );
+    }
+}
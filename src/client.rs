@@ -1,187 +1,87 @@
+use crate::batch::run_limited;
+use crate::cache::{
+    Cache, CacheMode, DEFAULT_CACHE_MAX_SIZE_MB, DEFAULT_CACHE_TTL_SECS, load_cached,
+    require_online, store_fetched,
+};
 use crate::crt::Crt;
-use cli_table::format::Justify;
-use cli_table::{Cell, CellStruct, Style, Table, print_stdout};
+use crate::dedupe::dedupe_crts;
+use crate::error::{Error, Result};
+use crate::filter::Filters;
+use crate::query::Query;
+use crate::retry::{RetryEvent, RetryHook};
+use crate::transport::{block_on, execute_with_retry};
 use reqwest::Method;
 use reqwest::blocking::Client;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use serde_json::{Value, from_str};
+use serde_json::from_str;
 use std::collections::HashMap;
-use std::error::Error;
 use std::fs::File;
 use std::io::Read;
-use std::{io, thread};
-use std::time::{Duration, Instant};
-use csv::Writer;
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
+use std::time::Duration;
 
 // アプリケーション情報
-const USER_AGENT: &str = "rs-crtsh/1.0";
+pub(crate) const USER_AGENT: &str = "rs-crtsh/1.0";
 
-// デフォルト値
-pub(crate) const DEFAULT_RETRY_COUNT: u32 = 0;
-pub(crate) const DEFAULT_RETRY_DELAY: f64 = 1.0;
-pub(crate) const DEFAULT_TIMEOUT_SECS: u64 = 30;
-pub(crate) const DEFAULT_FORMAT: &str = "table";
-
-// リトライ関連
-const RETRY_BACKOFF_MULTIPLIER: f64 = 2.0;
-
-// ファイルサイズ計算
-const BYTES_PER_KB: f64 = 1024.0;
+// リクエストヘッダー
+pub(crate) const CONTENT_TYPE_JSON: &str = "application/json";
 
-// HTTPステータスコード
-const SERVER_ERROR_START: u16 = 500;
-const SERVER_ERROR_END: u16 = 599;
-const TOO_MANY_REQUESTS: u16 = 429;
-const REQUEST_TIMEOUT: u16 = 408;
-
-// 認証プレースホルダー
-const BASIC_AUTH_PLACEHOLDER: &str = "Basic <credentials>";
+// デフォルト値
+pub const DEFAULT_RETRY_COUNT: u32 = 0;
+pub const DEFAULT_RETRY_DELAY: f64 = 1.0;
+pub const DEFAULT_RETRY_MAX_DELAY: f64 = 60.0;
+pub const DEFAULT_TIMEOUT_SECS: u64 = 30;
+pub const DEFAULT_CONCURRENCY: usize = 2;
+pub const DEFAULT_REQUEST_DELAY: f64 = 1.0;
 
 // エラーメッセージ
-const ERROR_REQUEST_CLONE: &str = "Failed to clone request for retry";
-const ERROR_PRESET_NOT_FOUND: &str = "Preset '{}' not found in config file";
-const ERROR_NO_PRESETS: &str = "No presets found in config file";
+pub(crate) const ERROR_REQUEST_CLONE: &str = "Failed to clone request for retry";
+const ERROR_READ_CONFIG: &str = "Failed to read '{1}': {2}";
+const ERROR_PARSE_CONFIG: &str = "Failed to parse '{1}': {2}";
 const ERROR_UNKNOWN_METHOD: &str = "Unknown HTTP method";
 
-// 表示メッセージ
-const TIMING_HEADER: &str = "--- Timing Information ---";
-const RETRY_ATTEMPT_PREFIX: &str = "--- Retry Attempt {} ---";
-const RESPONSE_RECEIVED_MSG: &str = "Response received: {}";
-const BODY_READ_TIME_MSG: &str = "Body read time: {}";
-const TOTAL_TIME_MSG: &str = "Total time: {}";
-const RESPONSE_SIZE_MSG: &str = "Response size: {1} bytes ({2} KB)";
-const THROUGHPUT_MSG: &str = "Throughput: {} KB/s";
-const HTTP_RETRY_MSG: &str = "HTTP {} - retrying after delay...";
-const REQUEST_ERROR_RETRY_MSG: &str = "Request error: {} - retrying after delay...";
-
-// カラム名
-pub(crate) const COLUMN_ID: &str = "id";
-pub(crate) const COLUMN_COMMON_NAME: &str = "common_name";
-pub(crate) const COLUMN_ENTRY_TIMESTAMP: &str = "entry_timestamp";
-pub(crate) const COLUMN_ISSUER_CA_ID: &str = "issuer_ca_id";
-pub(crate) const COLUMN_ISSUER_NAME: &str = "issuer_name";
-pub(crate) const COLUMN_NAME_VALUE: &str = "name_value";
-pub(crate) const COLUMN_NOT_BEFORE: &str = "not_before";
-pub(crate) const COLUMN_NOT_AFTER: &str = "not_after";
-pub(crate) const COLUMN_RESULT_COUNT: &str = "result_count";
-pub(crate) const COLUMN_SERIAL_NUMBER: &str = "serial_number";
-
-// カラム定義
-struct ColumnDefinition {
-    column_name: &'static str,
-    csv_header: &'static str,
-    table_header: &'static str,
-    extract_csv_value: fn(&Crt) -> String,
-    extract_table_cell: fn(&Crt) -> CellStruct,
-}
-
-// 全カラムの定義
-const COLUMN_DEFINITIONS: &[ColumnDefinition] = &[
-    ColumnDefinition {
-        column_name: COLUMN_ID,
-        csv_header: "id",
-        table_header: "crt.sh ID",
-        extract_csv_value: |crt| crt.id.to_string(),
-        extract_table_cell: |crt| crt.id.cell().justify(Justify::Right),
-    },
-    ColumnDefinition {
-        column_name: COLUMN_COMMON_NAME,
-        csv_header: "Matching Identities",
-        table_header: "Matching Identities",
-        extract_csv_value: |crt| crt.common_name.clone(),
-        extract_table_cell: |crt| crt.common_name.clone().cell(),
-    },
-    ColumnDefinition {
-        column_name: COLUMN_ENTRY_TIMESTAMP,
-        csv_header: "Logged At",
-        table_header: "Logged At",
-        extract_csv_value: |crt| crt.entry_timestamp.clone().unwrap_or_default(),
-        extract_table_cell: |crt| crt.entry_timestamp.clone().unwrap_or_default().cell(),
-    },
-    ColumnDefinition {
-        column_name: COLUMN_ISSUER_CA_ID,
-        csv_header: "Issuer CA ID",
-        table_header: "Issuer CA ID",
-        extract_csv_value: |crt| crt.issuer_ca_id.to_string(),
-        extract_table_cell: |crt| crt.issuer_ca_id.to_string().cell().justify(Justify::Right),
-    },
-    ColumnDefinition {
-        column_name: COLUMN_ISSUER_NAME,
-        csv_header: "Issuer Name",
-        table_header: "Issuer Name",
-        extract_csv_value: |crt| crt.issuer_name.clone(),
-        extract_table_cell: |crt| crt.issuer_name.clone().cell(),
-    },
-    ColumnDefinition {
-        column_name: COLUMN_NAME_VALUE,
-        csv_header: "Name Value",
-        table_header: "Name Value",
-        extract_csv_value: |crt| crt.name_value.clone(),
-        extract_table_cell: |crt| crt.name_value.clone().cell(),
-    },
-    ColumnDefinition {
-        column_name: COLUMN_NOT_BEFORE,
-        csv_header: "Not Before",
-        table_header: "Not Before",
-        extract_csv_value: |crt| crt.not_before.clone(),
-        extract_table_cell: |crt| crt.not_before.clone().cell(),
-    },
-    ColumnDefinition {
-        column_name: COLUMN_NOT_AFTER,
-        csv_header: "Not After",
-        table_header: "Not After",
-        extract_csv_value: |crt| crt.not_after.clone(),
-        extract_table_cell: |crt| crt.not_after.clone().cell(),
-    },
-    ColumnDefinition {
-        column_name: COLUMN_RESULT_COUNT,
-        csv_header: "Count",
-        table_header: "Count",
-        extract_csv_value: |crt| crt.result_count.to_string(),
-        extract_table_cell: |crt| crt.result_count.to_string().cell().justify(Justify::Right),
-    },
-    ColumnDefinition {
-        column_name: COLUMN_SERIAL_NUMBER,
-        csv_header: "Serial Number",
-        table_header: "Serial Number",
-        extract_csv_value: |crt| crt.serial_number.clone(),
-        extract_table_cell: |crt| crt.serial_number.clone().cell(),
-    },
-];
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum Format {
-    Csv,
-    Raw,
-    Table,
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub retry: u32,
     pub retry_delay: f64,
+    pub retry_max_delay: f64,
+    pub retry_jitter: bool,
+    pub retry_budget: Option<f64>,
     pub timeout: u64,
-    pub timing: bool,
-    pub url: String,
-    pub verbose: bool,
-    pub format: Format,
-    pub column_names: Vec<String>,
+    pub filters: Filters,
+    pub dedupe: bool,
+    pub concurrency: usize,
+    pub request_delay: f64,
+    pub cache_mode: CacheMode,
+    /// キャッシュの保存先（None の場合はキャッシュを使用しない、デフォルトは None）
+    pub cache_dir: Option<PathBuf>,
+    pub cache_ttl: Duration,
+    /// キャッシュの合計サイズの上限（MB）
+    pub cache_max_size: u64,
 }
 
 #[derive(Debug, Deserialize)]
-struct ConfigFile {
-    preset: HashMap<String, ConfigPreset>,
+struct ConfigFile<T> {
+    preset: HashMap<String, T>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
-struct ConfigPreset {
-    url: Option<String>,
+/// 設定ファイルのプリセットのうちクライアントの設定（それ以外のキーは無視する）
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ConfigPreset {
     timeout: Option<u64>,
-    timing: Option<bool>,
-    verbose: Option<bool>,
     retry: Option<u32>,
     retry_delay: Option<f64>,
-    format: Option<String>,
+    retry_max_delay: Option<f64>,
+    retry_jitter: Option<bool>,
+    retry_budget: Option<f64>,
+    concurrency: Option<NonZeroUsize>,
+    request_delay: Option<f64>,
+    cache: Option<bool>,
+    cache_dir: Option<PathBuf>,
+    cache_ttl: Option<u64>,
+    cache_max_size: Option<u64>,
 }
 
 impl Default for Config {
@@ -189,35 +89,50 @@ impl Default for Config {
         Config {
             retry: DEFAULT_RETRY_COUNT,
             retry_delay: DEFAULT_RETRY_DELAY,
+            retry_max_delay: DEFAULT_RETRY_MAX_DELAY,
+            retry_jitter: false,
+            retry_budget: None,
             timeout: DEFAULT_TIMEOUT_SECS,
-            timing: false,
-            url: String::new(),
-            verbose: false,
-            format: Format::Table,
-            column_names: Vec::new(),
+            filters: Filters::default(),
+            dedupe: false,
+            concurrency: DEFAULT_CONCURRENCY,
+            request_delay: DEFAULT_REQUEST_DELAY,
+            cache_mode: CacheMode::default(),
+            cache_dir: None,
+            cache_ttl: Duration::from_secs(DEFAULT_CACHE_TTL_SECS),
+            cache_max_size: DEFAULT_CACHE_MAX_SIZE_MB,
         }
     }
 }
 
 #[derive(Debug)]
-struct ResponseInfo {
+pub struct ResponseInfo {
     status: reqwest::StatusCode,
     version: reqwest::Version,
     headers: reqwest::header::HeaderMap,
 }
 
 #[derive(Debug)]
-struct TimingInfo {
+pub struct TimingInfo {
     response_time: Duration,
     body_read_time: Duration,
     total_time: Duration,
 }
 
+/// HTTP レスポンス（ヘッダー情報・ボディ・タイミング）
+#[derive(Debug)]
+pub struct Response {
+    pub info: ResponseInfo,
+    pub body: String,
+    pub timing: TimingInfo,
+    /// キャッシュから取得した場合は取得からの経過時間
+    pub cache_age: Option<Duration>,
+}
+
 #[derive(Debug)]
 struct RequestContext {
     client: Client,
     request: reqwest::blocking::Request,
-    default_headers: reqwest::header::HeaderMap,
 }
 
 impl ResponseInfo {
@@ -246,6 +161,22 @@ impl ResponseInfo {
     }
 }
 
+impl Response {
+    /// 受信したレスポンスから作成（2xx 以外のステータスは HTTP エラー）
+    pub(crate) fn received(info: ResponseInfo, body: String, timing: TimingInfo) -> Result<Self> {
+        if !info.status.is_success() {
+            return Err(Error::http_status(info.status, &body));
+        }
+
+        Ok(Self {
+            info,
+            body,
+            timing,
+            cache_age: None,
+        })
+    }
+}
+
 impl TimingInfo {
     pub fn new(response_time: Duration, body_read_time: Duration, total_time: Duration) -> Self {
         Self {
@@ -254,112 +185,255 @@ impl TimingInfo {
             total_time,
         }
     }
+
+    pub fn response_time(&self) -> Duration {
+        self.response_time
+    }
+
+    pub fn body_read_time(&self) -> Duration {
+        self.body_read_time
+    }
+
+    pub fn total_time(&self) -> Duration {
+        self.total_time
+    }
+}
+
+/// crt.sh API クライアント
+pub struct CrtShClient {
+    config: Config,
+    client: Client,
+    default_headers: reqwest::header::HeaderMap,
+    retry_hook: Option<RetryHook>,
+    cache: Option<Cache>,
+}
+
+impl CrtShClient {
+    /// 設定からクライアントを作成
+    pub fn new(config: Config) -> Result<Self> {
+        let (client, default_headers) = create_http_client(&config)?;
+        let cache = Cache::from_config(&config);
+
+        Ok(Self {
+            config,
+            client,
+            default_headers,
+            retry_hook: None,
+            cache,
+        })
+    }
+
+    /// リトライ時のフックを設定
+    pub fn on_retry(mut self, hook: impl Fn(&RetryEvent) + Send + Sync + 'static) -> Self {
+        self.retry_hook = Some(Box::new(hook));
+        self
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// すべてのリクエストに付与されるヘッダー
+    pub fn default_headers(&self) -> &reqwest::header::HeaderMap {
+        &self.default_headers
+    }
+
+    /// クエリで検索して証明書の一覧を取得（設定の重複統合・絞り込み条件を適用）
+    pub fn search(&self, query: &Query) -> Result<Vec<Crt>> {
+        let response = self.fetch(&query.to_url())?;
+
+        let mut crts = parse_crts(&response.body)?;
+        if self.config.dedupe {
+            crts = dedupe_crts(crts);
+        }
+
+        Ok(self.config.filters.apply(crts))
+    }
+
+    /// 複数の URL を取得（同時実行数・リクエスト間隔は設定に従い、結果は入力と同じ順）
+    ///
+    /// キャッシュから取得できる URL はリクエスト間隔を空けずに返す。
+    pub fn fetch_all(&self, urls: &[String]) -> Vec<Result<Response>> {
+        let interval = Duration::from_secs_f64(self.config.request_delay.max(0.0));
+
+        let cached: Vec<Option<Response>> = urls
+            .iter()
+            .map(|url| self.cache.as_ref().and_then(|cache| cache.load(url)))
+            .collect();
+        let missing: Vec<&String> = urls
+            .iter()
+            .zip(&cached)
+            .filter(|(_, response)| response.is_none())
+            .map(|(url, _)| url)
+            .collect();
+
+        let mut fetched = run_limited(&missing, self.config.concurrency, interval, |url| {
+            self.fetch_uncached(url)
+        })
+        .into_iter();
+
+        cached
+            .into_iter()
+            .filter_map(|response| response.map(Ok).or_else(|| fetched.next()))
+            .collect()
+    }
+
+    /// URL を取得してレスポンスをそのまま返す（キャッシュがあればキャッシュから返す）
+    pub fn fetch(&self, url: &str) -> Result<Response> {
+        if let Some(response) = load_cached(self.cache.as_ref(), self.config.cache_mode, url)? {
+            return Ok(response);
+        }
+
+        self.fetch_uncached(url)
+    }
+
+    /// キャッシュを確認済みの URL を取得して保存（オフラインの場合はエラー）
+    fn fetch_uncached(&self, url: &str) -> Result<Response> {
+        require_online(self.cache.as_ref(), self.config.cache_mode, url)?;
+
+        let request_context = create_request_context(&self.client, url)?;
+
+        let response = block_on(execute_with_retry(
+            &request_context.client,
+            request_context.request,
+            &self.config,
+            self.retry_hook.as_ref(),
+        ))?;
+
+        store_fetched(self.cache.as_ref(), url, &response);
+        Ok(response)
+    }
+
+    /// JSON を POST してレスポンスを返す（Webhook の送信などに使用、リトライ設定に従う）
+    pub fn post_json(&self, url: &str, body: String) -> Result<Response> {
+        let request = create_request_builder(&self.client, &Method::POST, url)?
+            .header(reqwest::header::CONTENT_TYPE, CONTENT_TYPE_JSON)
+            .body(body)
+            .build()?;
+
+        block_on(execute_with_retry(
+            &self.client,
+            request,
+            &self.config,
+            self.retry_hook.as_ref(),
+        ))
+    }
 }
 
 /// 設定ファイルを読み込んでConfigを作成
 pub fn load_config_file(
     config_path: &str,
     preset_name: Option<&str>,
-) -> Result<Config, Box<dyn Error>> {
-    let mut file = File::open(config_path)?;
+) -> Result<Config> {
+    load_preset::<ConfigPreset>(config_path, preset_name).map(Config::from)
+}
+
+/// 設定ファイルからプリセットを読み込む（プリセット名を省略した場合は最初のプリセット）
+///
+/// クライアント以外の設定（出力形式など）を同じプリセットから読み込む場合は、
+/// `#[serde(flatten)]` で ConfigPreset を含む型を指定する。
+pub fn load_preset<T: DeserializeOwned>(
+    config_path: &str,
+    preset_name: Option<&str>,
+) -> Result<T> {
     let mut contents = String::new();
-    file.read_to_string(&mut contents)?;
+    File::open(config_path)
+        .and_then(|mut file| file.read_to_string(&mut contents))
+        .map_err(|e| {
+            Error::Config(
+                ERROR_READ_CONFIG
+                    .replace("{1}", config_path)
+                    .replace("{2}", &e.to_string()),
+            )
+        })?;
 
-    let config_file: ConfigFile = toml::from_str(&contents)?;
+    let config_file: ConfigFile<T> = toml::from_str(&contents).map_err(|e| {
+        Error::Config(
+            ERROR_PARSE_CONFIG
+                .replace("{1}", config_path)
+                .replace("{2}", &e.to_string()),
+        )
+    })?;
 
-    let preset = get_preset(&config_file, preset_name)?;
-    Ok(create_config_from_preset(preset))
+    get_preset(config_file, preset_name)
+}
+
+/// レスポンスボディを証明書の一覧に変換
+pub fn parse_crts(body: &str) -> Result<Vec<Crt>> {
+    from_str(body).map_err(|e| Error::json(body, e))
 }
 
 /// プリセットを取得
-fn get_preset<'a>(
-    config_file: &'a ConfigFile,
-    preset_name: Option<&'a str>,
-) -> Result<&'a ConfigPreset, Box<dyn Error>> {
+fn get_preset<T>(config_file: ConfigFile<T>, preset_name: Option<&str>) -> Result<T> {
+    let mut presets = config_file.preset;
+
     match preset_name {
-        Some(name) => config_file
-            .preset
-            .get(name)
-            .ok_or_else(|| format!("{}", ERROR_PRESET_NOT_FOUND.replace("{}", name)).into()),
-        None => config_file
-            .preset
-            .values()
+        Some(name) => presets
+            .remove(name)
+            .ok_or_else(|| Error::PresetNotFound(Some(name.to_string()))),
+        None => presets
+            .into_values()
             .next()
-            .ok_or(ERROR_NO_PRESETS.into()),
+            .ok_or(Error::PresetNotFound(None)),
     }
 }
 
-/// プリセットからConfigを作成
-fn create_config_from_preset(preset: &ConfigPreset) -> Config {
-    Config {
-        retry: preset.retry.unwrap_or(DEFAULT_RETRY_COUNT),
-        retry_delay: preset.retry_delay.unwrap_or(DEFAULT_RETRY_DELAY),
-        timeout: preset.timeout.unwrap_or(DEFAULT_TIMEOUT_SECS),
-        timing: preset.timing.unwrap_or(false),
-        url: preset.url.clone().unwrap_or_default(),
-        verbose: preset.verbose.unwrap_or(false),
-        format: match preset
-            .format
-            .clone()
-            .unwrap_or(DEFAULT_FORMAT.to_string())
-            .as_str()
-        {
-            "csv" => Format::Csv,
-            "raw" => Format::Raw,
-            "table" => Format::Table,
-            _ => Format::Table,
-        },
-        column_names: Vec::new(),
+impl From<ConfigPreset> for Config {
+    /// プリセットからConfigを作成
+    fn from(preset: ConfigPreset) -> Self {
+        Config {
+            retry: preset.retry.unwrap_or(DEFAULT_RETRY_COUNT),
+            retry_delay: preset.retry_delay.unwrap_or(DEFAULT_RETRY_DELAY),
+            retry_max_delay: preset.retry_max_delay.unwrap_or(DEFAULT_RETRY_MAX_DELAY),
+            retry_jitter: preset.retry_jitter.unwrap_or(false),
+            retry_budget: preset.retry_budget,
+            timeout: preset.timeout.unwrap_or(DEFAULT_TIMEOUT_SECS),
+            filters: Filters::default(),
+            dedupe: false,
+            concurrency: preset.concurrency.map_or(DEFAULT_CONCURRENCY, NonZeroUsize::get),
+            request_delay: preset.request_delay.unwrap_or(DEFAULT_REQUEST_DELAY),
+            cache_mode: match preset.cache {
+                Some(false) => CacheMode::Disabled,
+                _ => CacheMode::default(),
+            },
+            cache_dir: preset.cache_dir,
+            cache_ttl: Duration::from_secs(preset.cache_ttl.unwrap_or(DEFAULT_CACHE_TTL_SECS)),
+            cache_max_size: preset.cache_max_size.unwrap_or(DEFAULT_CACHE_MAX_SIZE_MB),
+        }
     }
 }
 
-/// HTTPリクエストを実行
-pub fn execute_request(config: Config) -> Result<(), Box<dyn Error>> {
-    let request_context = create_request_context(&config)?;
-
-    display_request_info(&config, &request_context);
-
-    let (response_info, response_body, timing_info) =
-        execute_request_with_retry(&request_context.client, request_context.request, &config)?;
-
-    handle_response(response_info, response_body, timing_info, &config)?;
-
-    Ok(())
-}
-
 /// HTTPクライアントとリクエストを作成
-fn create_request_context(config: &Config) -> Result<RequestContext, Box<dyn Error>> {
-    let (client, default_headers) = create_http_client(config)?;
-    let request = build_request(&client, config)?;
+fn create_request_context(client: &Client, url: &str) -> Result<RequestContext> {
+    let request = build_request(client, url)?;
 
     Ok(RequestContext {
-        client,
+        client: client.clone(),
         request,
-        default_headers,
     })
 }
 
 /// HTTPクライアントを作成
 fn create_http_client(
     config: &Config,
-) -> Result<(Client, reqwest::header::HeaderMap), Box<dyn Error>> {
+) -> Result<(Client, reqwest::header::HeaderMap)> {
     let client_builder = Client::builder()
         .timeout(Duration::from_secs(config.timeout))
         .user_agent(USER_AGENT);
 
     let mut default_headers = reqwest::header::HeaderMap::new();
-    default_headers.insert(reqwest::header::USER_AGENT, USER_AGENT.parse()?);
+    default_headers.insert(
+        reqwest::header::USER_AGENT,
+        reqwest::header::HeaderValue::from_static(USER_AGENT),
+    );
 
     Ok((client_builder.build()?, default_headers))
 }
 
 /// HTTPリクエストを構築
-fn build_request(
-    client: &Client,
-    config: &Config,
-) -> Result<reqwest::blocking::Request, Box<dyn Error>> {
+fn build_request(client: &Client, url: &str) -> Result<reqwest::blocking::Request> {
     let method = Method::GET;
-    let request_builder = create_request_builder(client, &method, &config.url)?;
+    let request_builder = create_request_builder(client, &method, url)?;
 
     Ok(request_builder.build()?)
 }
@@ -369,7 +443,7 @@ fn create_request_builder(
     client: &Client,
     method: &Method,
     url: &str,
-) -> Result<reqwest::blocking::RequestBuilder, Box<dyn Error>> {
+) -> Result<reqwest::blocking::RequestBuilder> {
     let request_builder = match *method {
         Method::GET => client.get(url),
         Method::POST => client.post(url),
@@ -377,307 +451,8 @@ fn create_request_builder(
         Method::DELETE => client.delete(url),
         Method::HEAD => client.head(url),
         Method::PATCH => client.patch(url),
-        _ => return Err(ERROR_UNKNOWN_METHOD.into()),
+        _ => return Err(Error::Config(ERROR_UNKNOWN_METHOD.to_string())),
     };
 
     Ok(request_builder)
 }
-
-/// リクエスト情報を表示
-fn display_request_info(config: &Config, context: &RequestContext) {
-    if !config.verbose {
-        return;
-    }
-
-    println!("> GET {}", config.url);
-
-    for (name, value) in &context.default_headers {
-        let display_value = if name == reqwest::header::AUTHORIZATION {
-            BASIC_AUTH_PLACEHOLDER
-        } else {
-            value.to_str().unwrap_or("<binary>")
-        };
-        println!("> {}: {}", name, display_value);
-    }
-
-    for (name, value) in context.request.headers() {
-        if !context.default_headers.contains_key(name) {
-            println!("> {}: {}", name, value.to_str().unwrap_or("<binary>"));
-        }
-    }
-
-    println!();
-}
-
-/// リトライ機能付きでリクエストを実行
-fn execute_request_with_retry(
-    client: &Client,
-    request: reqwest::blocking::Request,
-    config: &Config,
-) -> Result<(ResponseInfo, String, TimingInfo), Box<dyn Error>> {
-    let mut current_attempt: u32 = 0;
-    let max_attempts: u32 = config.retry + 1;
-    let overall_start = Instant::now();
-
-    loop {
-        current_attempt += 1;
-
-        let retry_request = request.try_clone().ok_or(ERROR_REQUEST_CLONE)?;
-
-        if config.verbose && current_attempt > 1 {
-            println!(
-                "{}",
-                RETRY_ATTEMPT_PREFIX.replace("{}", &current_attempt.saturating_sub(1).to_string())
-            );
-        }
-
-        let request_start = Instant::now();
-
-        return match client.execute(retry_request) {
-            Ok(response) => {
-                let status = response.status();
-
-                if should_retry_for_status(status.as_u16()) && current_attempt < max_attempts {
-                    handle_retry_delay(config, current_attempt, status.as_u16());
-                    continue;
-                }
-
-                handle_successful_response(response, request_start, overall_start)
-            }
-            Err(e) => {
-                if current_attempt < max_attempts {
-                    handle_request_error_retry(config, current_attempt, &e);
-                    continue;
-                }
-
-                Err(e.into())
-            }
-        }
-    }
-}
-
-/// 成功したレスポンスを処理
-fn handle_successful_response(
-    response: reqwest::blocking::Response,
-    request_start: Instant,
-    overall_start: Instant,
-) -> Result<(ResponseInfo, String, TimingInfo), Box<dyn Error>> {
-    let response_received_time = request_start.elapsed();
-
-    let status_code = response.status();
-    let version = response.version();
-    let headers = response.headers().clone();
-
-    let body_start = Instant::now();
-    let response_body = response.text()?;
-    let body_read_time = body_start.elapsed();
-
-    let total_time = overall_start.elapsed();
-
-    let response_info = ResponseInfo::new(status_code, version, headers);
-    let timing_info = TimingInfo::new(response_received_time, body_read_time, total_time);
-
-    Ok((response_info, response_body, timing_info))
-}
-
-/// リトライ遅延を処理
-fn handle_retry_delay(config: &Config, current_attempt: u32, status_code: u16) {
-    if config.verbose {
-        println!("{}", HTTP_RETRY_MSG.replace("{}", &status_code.to_string()));
-    }
-
-    let backoff_delay = config.retry_delay
-        * RETRY_BACKOFF_MULTIPLIER.powi(current_attempt.saturating_sub(1) as i32);
-    thread::sleep(Duration::from_secs_f64(backoff_delay));
-}
-
-/// リクエストエラーのリトライを処理
-fn handle_request_error_retry(config: &Config, current_attempt: u32, error: &reqwest::Error) {
-    if config.verbose {
-        println!(
-            "{}",
-            REQUEST_ERROR_RETRY_MSG.replace("{}", &error.to_string())
-        );
-    }
-
-    let backoff_delay = config.retry_delay
-        * RETRY_BACKOFF_MULTIPLIER.powi(current_attempt.saturating_sub(1) as i32);
-    thread::sleep(Duration::from_secs_f64(backoff_delay));
-}
-
-/// ステータスコードによるリトライ判定
-fn should_retry_for_status(status_code: u16) -> bool {
-    matches!(
-        status_code,
-        SERVER_ERROR_START..=SERVER_ERROR_END | TOO_MANY_REQUESTS | REQUEST_TIMEOUT
-    )
-}
-
-/// レスポンスを処理
-fn handle_response(
-    response_info: ResponseInfo,
-    response_body: String,
-    timing_info: TimingInfo,
-    config: &Config,
-) -> Result<(), Box<dyn Error>> {
-    display_response_info(&response_info, config);
-    display_timing_info(&timing_info, response_body.len(), config);
-
-    let processed_response = format_response_body(&response_body, config)?;
-    output_response(&processed_response, config)?;
-
-    Ok(())
-}
-
-/// レスポンス情報を表示
-fn display_response_info(response_info: &ResponseInfo, config: &Config) {
-    if !config.verbose {
-        return;
-    }
-
-    println!(
-        "< {:?} {} {}",
-        response_info.version(),
-        response_info.status().as_u16(),
-        response_info.status().canonical_reason().unwrap_or("")
-    );
-
-    for (name, value) in response_info.headers() {
-        println!("< {}: {}", name, value.to_str().unwrap_or("<binary>"));
-    }
-
-    println!();
-}
-
-/// タイミング情報を表示
-fn display_timing_info(timing_info: &TimingInfo, response_size: usize, config: &Config) {
-    if !config.timing {
-        return;
-    }
-
-    println!("{}", TIMING_HEADER);
-    println!(
-        "{}",
-        RESPONSE_RECEIVED_MSG.replace("{}", &format!("{:?}", timing_info.response_time))
-    );
-    println!(
-        "{}",
-        BODY_READ_TIME_MSG.replace("{}", &format!("{:?}", timing_info.body_read_time))
-    );
-    println!(
-        "{}",
-        TOTAL_TIME_MSG.replace("{}", &format!("{:?}", timing_info.total_time))
-    );
-    println!(
-        "{}",
-        RESPONSE_SIZE_MSG
-            .replace("{1}", &response_size.to_string())
-            .replace(
-                "{2}",
-                &format!("{:.2}", response_size as f64 / BYTES_PER_KB)
-            )
-    );
-
-    if response_size > 0 && timing_info.total_time.as_secs_f64() > 0.0 {
-        let throughput = response_size as f64 / timing_info.total_time.as_secs_f64() / BYTES_PER_KB;
-        println!(
-            "{}",
-            THROUGHPUT_MSG.replace("{}", &format!("{:.2}", throughput))
-        );
-    }
-
-    println!();
-}
-
-/// レスポンスボディをフォーマット
-fn format_response_body(body: &str, _config: &Config) -> Result<String, Box<dyn Error>> {
-    let json_value = match from_str::<Value>(body) {
-        Ok(value) => value,
-        Err(_) => return Ok(body.to_string()),
-    };
-
-    let result = json_value;
-
-    let formatted = serde_json::to_string(&result)?;
-
-    Ok(formatted)
-}
-
-/// 設定に基づいて有効なカラムをフィルタリング
-fn get_active_columns(config: &Config) -> Vec<&'static ColumnDefinition> {
-    COLUMN_DEFINITIONS
-        .iter()
-        .filter(|col| config.column_names.contains(&col.column_name.to_string()))
-        .collect()
-}
-
-/// CSVヘッダーを構築
-fn build_csv_header<'a>(active_columns: &'a [&'a ColumnDefinition]) -> Vec<&'a str> {
-    active_columns.iter().map(|col| col.csv_header).collect()
-}
-
-/// CSVデータ行を構築
-fn build_csv_row(crt: &Crt, active_columns: &[&ColumnDefinition]) -> Vec<String> {
-    active_columns
-        .iter()
-        .map(|col| (col.extract_csv_value)(crt))
-        .collect()
-}
-
-/// Tableヘッダーを構築
-fn build_table_header(active_columns: &[&ColumnDefinition]) -> Vec<CellStruct> {
-    active_columns
-        .iter()
-        .map(|col| col.table_header.cell().bold(true).justify(Justify::Center))
-        .collect()
-}
-
-/// Tableデータ行を構築
-fn build_table_row(crt: &Crt, active_columns: &[&ColumnDefinition]) -> Vec<CellStruct> {
-    active_columns
-        .iter()
-        .map(|col| (col.extract_table_cell)(crt))
-        .collect()
-}
-
-/// レスポンスを出力
-fn output_response(processed_response: &str, config: &Config) -> Result<(), Box<dyn Error>> {
-    match config.format {
-        Format::Csv => {
-            let crts: Vec<Crt> = from_str(processed_response)?;
-            let active_columns = get_active_columns(config);
-
-            let mut wtr = Writer::from_writer(io::stdout());
-
-            let header = build_csv_header(&active_columns);
-            wtr.write_record(header)?;
-
-            for crt in crts {
-                let row = build_csv_row(&crt, &active_columns);
-                wtr.write_record(row)?;
-            }
-
-            wtr.flush()?;
-        }
-        Format::Table => {
-            let crts: Vec<Crt> = from_str(processed_response)?;
-            let active_columns = get_active_columns(config);
-
-            let mut table = Vec::new();
-            for crt in crts {
-                let row = build_table_row(&crt, &active_columns);
-                table.push(row)
-            }
-
-            let header = build_table_header(&active_columns);
-            let ts = table.table().title(header);
-
-            assert!(print_stdout(ts).is_ok());
-        }
-        Format::Raw => {
-            println!("{}", processed_response);
-        }
-    }
-
-    Ok(())
-}
@@ -1,15 +1,51 @@
+use crate::dn::DistinguishedName;
+use crate::identity::{Identity, parse_identities};
+use crate::timestamp::Timestamp;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Crt {
     pub id: u64,
     pub common_name: String,
-    pub entry_timestamp: Option<String>, // NOTE: null が入ることがある
+    pub entry_timestamp: Option<Timestamp>, // NOTE: null が入ることがある
     pub issuer_ca_id: i64, // NOTE: -1 が入ることがある
     pub issuer_name: String,
     pub name_value: String,
-    pub not_after: String,
-    pub not_before: String,
+    pub not_after: Timestamp,
+    pub not_before: Timestamp,
     pub result_count: u32,
     pub serial_number: String,
+    /// 統合したログエントリの数（crt.sh の応答には含まれない）
+    #[serde(default = "default_log_entries", skip_serializing_if = "is_single_entry")]
+    pub log_entries: u32,
+    /// 一括検索で証明書を取得したクエリ（crt.sh の応答には含まれない）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+}
+
+impl Crt {
+    /// name_value を重複のない識別名の一覧として取得
+    pub fn identities(&self) -> Vec<Identity> {
+        parse_identities(&self.name_value)
+    }
+
+    /// 同一の発行（プレ証明書と証明書）を識別するキー
+    pub fn issuance_key(&self) -> (&str, i64) {
+        (&self.serial_number, self.issuer_ca_id)
+    }
+
+    /// issuer_name を識別名として解析
+    pub fn issuer(&self) -> DistinguishedName {
+        DistinguishedName::parse(&self.issuer_name)
+    }
+}
+
+/// log_entries の既定値（1 エントリ）
+fn default_log_entries() -> u32 {
+    1
+}
+
+/// 統合していない（1 エントリのみの）場合は出力しない
+fn is_single_entry(log_entries: &u32) -> bool {
+    *log_entries == 1
 }
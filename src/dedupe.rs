@@ -0,0 +1,122 @@
+use crate::crt::Crt;
+use std::borrow::BorrowMut;
+use std::collections::HashMap;
+
+/// 同じ発行（serial_number + issuer_ca_id）の証明書を 1 件にまとめる
+///
+/// entry_timestamp が最も早いものを残し、log_entries に統合したエントリ数を加算する。
+/// 並び順は各発行が最初に現れた位置を維持する。
+/// 証明書に元の行番号などを添えた型（`BorrowMut<Crt>`）もそのまま統合できる。
+pub fn dedupe_crts<T: BorrowMut<Crt>>(crts: Vec<T>) -> Vec<T> {
+    let mut kept: Vec<T> = Vec::new();
+    let mut positions: HashMap<(String, i64), usize> = HashMap::new();
+
+    for crt in crts {
+        let (serial_number, issuer_ca_id) = crt.borrow().issuance_key();
+        let key = (serial_number.to_string(), issuer_ca_id);
+
+        match positions.get(&key) {
+            Some(&position) => {
+                let existing = &mut kept[position];
+                let log_entries = existing.borrow().log_entries + crt.borrow().log_entries;
+                if is_logged_earlier(crt.borrow(), existing.borrow()) {
+                    *existing = crt;
+                }
+                existing.borrow_mut().log_entries = log_entries;
+            }
+            None => {
+                positions.insert(key, kept.len());
+                kept.push(crt);
+            }
+        }
+    }
+
+    kept
+}
+
+/// entry_timestamp が早いかどうか（null は最も遅いものとして扱う）
+fn is_logged_earlier(crt: &Crt, other: &Crt) -> bool {
+    match (&crt.entry_timestamp, &other.entry_timestamp) {
+        (Some(logged_at), Some(other_logged_at)) => logged_at < other_logged_at,
+        (Some(_), None) => true,
+        (None, _) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn crt(id: u64, serial_number: &str, issuer_ca_id: i64, entry_timestamp: Option<&str>) -> Crt {
+        serde_json::from_value(json!({
+            "id": id,
+            "common_name": "example.com",
+            "entry_timestamp": entry_timestamp,
+            "issuer_ca_id": issuer_ca_id,
+            "issuer_name": "C=GB, O=Sectigo Limited",
+            "name_value": "example.com",
+            "not_after": "2026-03-16T20:59:52",
+            "not_before": "2025-12-16T00:00:00",
+            "result_count": 1,
+            "serial_number": serial_number,
+        }))
+        .unwrap()
+    }
+
+    fn summary(crts: &[Crt]) -> Vec<(u64, u32)> {
+        crts.iter().map(|crt| (crt.id, crt.log_entries)).collect()
+    }
+
+    #[test]
+    fn merges_same_serial_and_issuer_keeping_earliest_entry() {
+        let crts = vec![
+            crt(2, "01", 1, Some("2025-12-16T21:50:44")),
+            crt(1, "01", 1, Some("2025-12-16T21:50:43")),
+            crt(3, "02", 1, Some("2025-12-16T21:50:45")),
+        ];
+
+        assert_eq!(summary(&dedupe_crts(crts)), [(1, 2), (3, 1)]);
+    }
+
+    #[test]
+    fn keeps_first_position_of_each_issuance() {
+        let crts = vec![
+            crt(1, "01", 1, Some("2025-12-16T21:50:43")),
+            crt(2, "02", 1, Some("2025-12-16T21:50:43")),
+            crt(3, "01", 1, Some("2025-12-16T21:50:40")),
+        ];
+
+        assert_eq!(summary(&dedupe_crts(crts)), [(3, 2), (2, 1)]);
+    }
+
+    #[test]
+    fn same_serial_from_different_issuers_is_not_merged() {
+        let crts = vec![
+            crt(1, "01", 1, Some("2025-12-16T21:50:43")),
+            crt(2, "01", 2, Some("2025-12-16T21:50:43")),
+        ];
+
+        assert_eq!(summary(&dedupe_crts(crts)), [(1, 1), (2, 1)]);
+    }
+
+    #[test]
+    fn missing_entry_timestamp_is_treated_as_latest() {
+        let crts = vec![
+            crt(1, "01", 1, None),
+            crt(2, "01", 1, Some("2025-12-16T21:50:43")),
+            crt(3, "01", 1, None),
+        ];
+
+        assert_eq!(summary(&dedupe_crts(crts)), [(2, 3)]);
+    }
+
+    #[test]
+    fn adds_log_entries_of_already_merged_certificates() {
+        let mut merged = crt(1, "01", 1, Some("2025-12-16T21:50:43"));
+        merged.log_entries = 2;
+        let crts = vec![merged, crt(2, "01", 1, Some("2025-12-16T21:50:44"))];
+
+        assert_eq!(summary(&dedupe_crts(crts)), [(1, 3)]);
+    }
+}
@@ -0,0 +1,158 @@
+use crate::crt::Crt;
+use crate::identity::Identity;
+use serde::Serialize;
+use std::collections::{BTreeSet, HashSet};
+
+/// 2 つの検索結果の差分
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Diff {
+    /// 新しい結果にのみ含まれる証明書（新しい結果の順）
+    pub added: Vec<Crt>,
+    /// 古い結果にのみ含まれる証明書（古い結果の順）
+    pub removed: Vec<Crt>,
+    /// 新しい結果で初めて現れた識別名
+    pub new_identities: Vec<Identity>,
+    /// 新しい結果に含まれなくなった識別名
+    pub removed_identities: Vec<Identity>,
+    /// 新しい結果で初めて現れた発行者
+    pub new_issuers: Vec<String>,
+}
+
+impl Diff {
+    /// 差分がないかどうか
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.new_identities.is_empty()
+            && self.removed_identities.is_empty()
+            && self.new_issuers.is_empty()
+    }
+}
+
+/// 古い結果と新しい結果を crt.sh ID で比較
+pub fn diff_crts(old: &[Crt], new: &[Crt]) -> Diff {
+    let old_ids: HashSet<u64> = old.iter().map(|crt| crt.id).collect();
+    let new_ids: HashSet<u64> = new.iter().map(|crt| crt.id).collect();
+
+    let old_identities = collect_identities(old);
+    let new_identities = collect_identities(new);
+
+    let old_issuers: HashSet<&str> = old.iter().map(|crt| crt.issuer_name.as_str()).collect();
+    let new_issuers: BTreeSet<&str> = new
+        .iter()
+        .map(|crt| crt.issuer_name.as_str())
+        .filter(|issuer| !old_issuers.contains(issuer))
+        .collect();
+
+    Diff {
+        added: unique_by_id(new.iter().filter(|crt| !old_ids.contains(&crt.id))),
+        removed: unique_by_id(old.iter().filter(|crt| !new_ids.contains(&crt.id))),
+        new_identities: new_identities.difference(&old_identities).cloned().collect(),
+        removed_identities: old_identities.difference(&new_identities).cloned().collect(),
+        new_issuers: new_issuers.into_iter().map(String::from).collect(),
+    }
+}
+
+/// 全証明書の識別名
+fn collect_identities(crts: &[Crt]) -> BTreeSet<Identity> {
+    crts.iter().flat_map(Crt::identities).collect()
+}
+
+/// ID の重複を除いて複製（一括検索で複数のクエリに同じ証明書が含まれる場合）
+fn unique_by_id<'a>(crts: impl Iterator<Item = &'a Crt>) -> Vec<Crt> {
+    let mut seen = HashSet::new();
+
+    crts.filter(|crt| seen.insert(crt.id)).cloned().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn crt(id: u64, name_value: &str, issuer_name: &str) -> Crt {
+        serde_json::from_value(json!({
+            "id": id,
+            "common_name": name_value.lines().next().unwrap(),
+            "entry_timestamp": "2025-12-16T21:50:43.225",
+            "issuer_ca_id": 1,
+            "issuer_name": issuer_name,
+            "name_value": name_value,
+            "not_after": "2026-03-16T20:59:52",
+            "not_before": "2025-12-16T00:00:00",
+            "result_count": 1,
+            "serial_number": format!("{:02x}", id),
+        }))
+        .unwrap()
+    }
+
+    fn ids(crts: &[Crt]) -> Vec<u64> {
+        crts.iter().map(|crt| crt.id).collect()
+    }
+
+    fn values(identities: &[Identity]) -> Vec<&str> {
+        identities
+            .iter()
+            .map(|identity| identity.value.as_str())
+            .collect()
+    }
+
+    const LE: &str = "C=US, O=Let's Encrypt, CN=R11";
+    const SECTIGO: &str = "C=GB, O=Sectigo Limited, CN=Sectigo CA";
+
+    #[test]
+    fn classifies_added_and_removed_certificates_by_id() {
+        let old = [crt(1, "a.example.com", LE), crt(2, "b.example.com", LE)];
+        let new = [
+            crt(3, "c.example.com", LE),
+            crt(2, "b.example.com", LE),
+            crt(4, "d.example.com", LE),
+        ];
+
+        let diff = diff_crts(&old, &new);
+
+        assert_eq!(ids(&diff.added), [3, 4]);
+        assert_eq!(ids(&diff.removed), [1]);
+    }
+
+    #[test]
+    fn reports_each_added_certificate_once() {
+        let new = [crt(1, "a.example.com", LE), crt(1, "a.example.com", LE)];
+
+        assert_eq!(ids(&diff_crts(&[], &new).added), [1]);
+    }
+
+    #[test]
+    fn compares_identities_across_all_certificates() {
+        let old = [crt(1, "a.example.com\nold.example.com", LE)];
+        let new = [crt(2, "A.example.com\nnew.example.com", LE)];
+
+        let diff = diff_crts(&old, &new);
+
+        assert_eq!(values(&diff.new_identities), ["new.example.com"]);
+        assert_eq!(values(&diff.removed_identities), ["old.example.com"]);
+    }
+
+    #[test]
+    fn reports_only_issuers_not_seen_before() {
+        let old = [crt(1, "a.example.com", LE)];
+        let new = [
+            crt(2, "a.example.com", SECTIGO),
+            crt(3, "b.example.com", LE),
+            crt(4, "c.example.com", SECTIGO),
+        ];
+
+        assert_eq!(diff_crts(&old, &new).new_issuers, [SECTIGO]);
+    }
+
+    #[test]
+    fn identical_results_have_no_changes() {
+        let crts = [
+            crt(1, "a.example.com", LE),
+            crt(2, "b.example.com", SECTIGO),
+        ];
+
+        assert!(diff_crts(&crts, &crts).is_empty());
+        assert!(!diff_crts(&crts, &crts[..1]).is_empty());
+    }
+}
@@ -0,0 +1,204 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// 属性名
+const ATTR_COUNTRY: &str = "C";
+const ATTR_ORGANIZATION: &str = "O";
+const ATTR_ORGANIZATIONAL_UNIT: &str = "OU";
+const ATTR_COMMON_NAME: &str = "CN";
+
+/// 識別名（DN）の 1 要素
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DnComponent {
+    pub attribute: String,
+    pub value: String,
+}
+
+/// 識別名（例: `C=GB, O=Sectigo Limited, CN=...`）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DistinguishedName {
+    components: Vec<DnComponent>,
+}
+
+impl DistinguishedName {
+    /// 識別名を解析（`\,` のエスケープと `"..."` の引用に対応）
+    pub fn parse(raw: &str) -> Self {
+        let components = split_rdns(raw)
+            .iter()
+            .filter_map(|rdn| parse_component(rdn))
+            .collect();
+
+        Self { components }
+    }
+
+    pub fn components(&self) -> &[DnComponent] {
+        &self.components
+    }
+
+    /// 属性の値を取得（大文字小文字を区別しない、複数ある場合は最初の値）
+    pub fn get(&self, attribute: &str) -> Option<&str> {
+        self.components
+            .iter()
+            .find(|component| component.attribute.eq_ignore_ascii_case(attribute))
+            .map(|component| component.value.as_str())
+    }
+
+    /// 国（C）
+    pub fn country(&self) -> Option<&str> {
+        self.get(ATTR_COUNTRY)
+    }
+
+    /// 組織（O）
+    pub fn organization(&self) -> Option<&str> {
+        self.get(ATTR_ORGANIZATION)
+    }
+
+    /// 部門（OU）
+    pub fn organizational_unit(&self) -> Option<&str> {
+        self.get(ATTR_ORGANIZATIONAL_UNIT)
+    }
+
+    /// 共通名（CN）
+    pub fn common_name(&self) -> Option<&str> {
+        self.get(ATTR_COMMON_NAME)
+    }
+}
+
+impl fmt::Display for DistinguishedName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts: Vec<String> = self
+            .components
+            .iter()
+            .map(|component| format!("{}={}", component.attribute, component.value))
+            .collect();
+
+        f.write_str(&parts.join(", "))
+    }
+}
+
+/// カンマで RDN に分割（エスケープと引用符の中のカンマは分割しない）
+fn split_rdns(raw: &str) -> Vec<String> {
+    let mut rdns = Vec::new();
+    let mut current = String::new();
+    let mut chars = raw.chars();
+    let mut quoted = false;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                current.push(c);
+                if let Some(escaped) = chars.next() {
+                    current.push(escaped);
+                }
+            }
+            '"' => {
+                quoted = !quoted;
+                current.push(c);
+            }
+            ',' | ';' if !quoted => rdns.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    rdns.push(current);
+
+    rdns
+}
+
+/// `属性=値` を解析して値のエスケープ・引用符を取り除く
+fn parse_component(rdn: &str) -> Option<DnComponent> {
+    let (attribute, value) = rdn.split_once('=')?;
+    let attribute = attribute.trim();
+    if attribute.is_empty() {
+        return None;
+    }
+
+    Some(DnComponent {
+        attribute: attribute.to_string(),
+        value: unescape_value(value.trim()),
+    })
+}
+
+/// 値のエスケープ（`\,` など）と前後の引用符を取り除く
+fn unescape_value(value: &str) -> String {
+    let value = value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value);
+
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unescaped.extend(chars.next()),
+            _ => unescaped.push(c),
+        }
+    }
+
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_common_issuer() {
+        let dn = DistinguishedName::parse(
+            "C=GB, O=Sectigo Limited, CN=Sectigo Public Server Authentication CA DV E36",
+        );
+
+        assert_eq!(dn.country(), Some("GB"));
+        assert_eq!(dn.organization(), Some("Sectigo Limited"));
+        assert_eq!(dn.organizational_unit(), None);
+        assert_eq!(
+            dn.common_name(),
+            Some("Sectigo Public Server Authentication CA DV E36")
+        );
+    }
+
+    #[test]
+    fn keeps_comma_inside_quoted_value() {
+        let dn = DistinguishedName::parse(r#"C=US, O="Sectigo Limited, Inc", CN=Example CA"#);
+
+        assert_eq!(dn.components().len(), 3);
+        assert_eq!(dn.organization(), Some("Sectigo Limited, Inc"));
+        assert_eq!(dn.common_name(), Some("Example CA"));
+    }
+
+    #[test]
+    fn unescapes_escaped_comma() {
+        let dn = DistinguishedName::parse(r"C=US, O=DigiCert\, Inc., OU=www.digicert.com");
+
+        assert_eq!(dn.organization(), Some("DigiCert, Inc."));
+        assert_eq!(dn.organizational_unit(), Some("www.digicert.com"));
+    }
+
+    #[test]
+    fn unescapes_quote_and_backslash() {
+        let dn = DistinguishedName::parse(r#"O=Example \"Trust\" \\ Services, CN=CA"#);
+
+        assert_eq!(dn.organization(), Some(r#"Example "Trust" \ Services"#));
+        assert_eq!(dn.common_name(), Some("CA"));
+    }
+
+    #[test]
+    fn looks_up_attributes_case_insensitively() {
+        let dn = DistinguishedName::parse("c=JP; o=Example; cn=Example CA");
+
+        assert_eq!(dn.country(), Some("JP"));
+        assert_eq!(dn.get("CN"), Some("Example CA"));
+    }
+
+    #[test]
+    fn skips_components_without_attribute() {
+        let dn = DistinguishedName::parse("Example, =Empty, CN=Example CA");
+
+        assert_eq!(
+            dn.components(),
+            [DnComponent {
+                attribute: "CN".to_string(),
+                value: "Example CA".to_string(),
+            }]
+        );
+    }
+}
@@ -0,0 +1,165 @@
+use std::fmt;
+
+// 終了コード
+// （2 は clap が引数の解析エラーで使用するため、重複しないよう 10 から割り当てる）
+pub const EXIT_INVALID_QUERY: u8 = 10;
+pub const EXIT_CONFIG: u8 = 11;
+pub const EXIT_PRESET: u8 = 12;
+pub const EXIT_HTTP_STATUS: u8 = 13;
+pub const EXIT_TIMEOUT: u8 = 14;
+pub const EXIT_REQUEST: u8 = 15;
+pub const EXIT_JSON: u8 = 16;
+pub const EXIT_OUTPUT: u8 = 17;
+pub const EXIT_BATCH: u8 = 18;
+pub const EXIT_CACHE: u8 = 19;
+
+// レスポンスボディの抜粋の最大文字数
+const BODY_SNIPPET_LEN: usize = 200;
+
+// エラーメッセージ
+const ERROR_CONFIG: &str = "Config error: {}";
+const ERROR_PRESET_NOT_FOUND: &str = "Preset '{}' not found in config file";
+const ERROR_NO_PRESETS: &str = "No presets found in config file";
+const ERROR_INVALID_QUERY: &str = "Invalid query: {}";
+const ERROR_HTTP_STATUS: &str = "HTTP {1}: {2}";
+const ERROR_TIMEOUT: &str = "Request timed out: {}";
+const ERROR_REQUEST: &str = "Request failed: {}";
+const ERROR_JSON: &str = "Failed to decode JSON at byte {1}: {2}";
+const ERROR_OUTPUT: &str = "Output error: {}";
+const ERROR_BATCH: &str = "{1} of {2} queries failed";
+const ERROR_CACHE: &str = "Cache error: {}";
+
+/// rs-crtsh のエラー
+#[derive(Debug)]
+pub enum Error {
+    /// 設定ファイルやオプションの誤り
+    Config(String),
+    /// プリセットが見つからない（None はプリセットが 1 つもない）
+    PresetNotFound(Option<String>),
+    /// 検索パラメータの検証エラー
+    InvalidQuery(String),
+    /// 成功以外の HTTP ステータス
+    HttpStatus {
+        status: reqwest::StatusCode,
+        body: String,
+    },
+    /// タイムアウト
+    Timeout(reqwest::Error),
+    /// タイムアウト以外の通信エラー
+    Request(reqwest::Error),
+    /// JSON のデコードエラー（offset はボディ先頭からのバイト位置）
+    Json {
+        offset: usize,
+        source: serde_json::Error,
+    },
+    /// 出力時のエラー
+    Output(String),
+    /// 一括検索の一部のクエリが失敗
+    BatchFailed { failed: usize, total: usize },
+    /// キャッシュの読み書きの失敗、またはオフライン時のキャッシュなし
+    Cache(String),
+}
+
+/// rs-crtsh の Result
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    /// HTTP ステータスエラーを作成（ボディは先頭のみ保持）
+    pub fn http_status(status: reqwest::StatusCode, body: &str) -> Self {
+        Error::HttpStatus {
+            status,
+            body: body.trim().chars().take(BODY_SNIPPET_LEN).collect(),
+        }
+    }
+
+    /// JSON のデコードエラーを作成
+    pub fn json(body: &str, source: serde_json::Error) -> Self {
+        Error::Json {
+            offset: byte_offset(body, source.line(), source.column()),
+            source,
+        }
+    }
+
+    /// 出力エラーを作成
+    pub fn output(error: impl fmt::Display) -> Self {
+        Error::Output(error.to_string())
+    }
+
+    /// キャッシュエラーを作成
+    pub fn cache(error: impl fmt::Display) -> Self {
+        Error::Cache(error.to_string())
+    }
+
+    /// エラーの種類に応じたプロセスの終了コード
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Error::Config(_) => EXIT_CONFIG,
+            Error::PresetNotFound(_) => EXIT_PRESET,
+            Error::InvalidQuery(_) => EXIT_INVALID_QUERY,
+            Error::HttpStatus { .. } => EXIT_HTTP_STATUS,
+            Error::Timeout(_) => EXIT_TIMEOUT,
+            Error::Request(_) => EXIT_REQUEST,
+            Error::Json { .. } => EXIT_JSON,
+            Error::Output(_) => EXIT_OUTPUT,
+            Error::BatchFailed { .. } => EXIT_BATCH,
+            Error::Cache(_) => EXIT_CACHE,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            Error::Config(message) => ERROR_CONFIG.replace("{}", message),
+            Error::PresetNotFound(Some(name)) => ERROR_PRESET_NOT_FOUND.replace("{}", name),
+            Error::PresetNotFound(None) => ERROR_NO_PRESETS.to_string(),
+            Error::InvalidQuery(message) => ERROR_INVALID_QUERY.replace("{}", message),
+            Error::HttpStatus { status, body } => ERROR_HTTP_STATUS
+                .replace("{1}", &status.to_string())
+                .replace("{2}", body),
+            Error::Timeout(error) => ERROR_TIMEOUT.replace("{}", &error.to_string()),
+            Error::Request(error) => ERROR_REQUEST.replace("{}", &error.to_string()),
+            Error::Json { offset, source } => ERROR_JSON
+                .replace("{1}", &offset.to_string())
+                .replace("{2}", &source.to_string()),
+            Error::Output(message) => ERROR_OUTPUT.replace("{}", message),
+            Error::BatchFailed { failed, total } => ERROR_BATCH
+                .replace("{1}", &failed.to_string())
+                .replace("{2}", &total.to_string()),
+            Error::Cache(message) => ERROR_CACHE.replace("{}", message),
+        };
+
+        f.write_str(&message)
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Timeout(error) | Error::Request(error) => Some(error),
+            Error::Json { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(error: reqwest::Error) -> Self {
+        if error.is_timeout() {
+            Error::Timeout(error)
+        } else {
+            Error::Request(error)
+        }
+    }
+}
+
+/// 行・列（1 始まり）をバイト位置に変換
+fn byte_offset(text: &str, line: usize, column: usize) -> usize {
+    let line_start: usize = text
+        .split_inclusive('\n')
+        .take(line.saturating_sub(1))
+        .map(str::len)
+        .sum();
+
+    (line_start + column.saturating_sub(1)).min(text.len())
+}
@@ -0,0 +1,408 @@
+use crate::crt::Crt;
+use crate::identity::Identity;
+use crate::pattern::Pattern;
+use crate::timespec::TimeSpec;
+use crate::timestamp::Timestamp;
+use chrono::{NaiveDateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+// ワイルドカードの接頭辞
+const WILDCARD_PREFIX: &str = "*.";
+
+/// 有効期間による絞り込み
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Validity {
+    /// 基準日時に有効な証明書のみ
+    Valid,
+    /// 基準日時に期限切れの証明書のみ
+    Expired,
+}
+
+/// 取得した証明書の絞り込み条件
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Filters {
+    pub validity: Option<Validity>,
+    /// 基準日時からこの期間内に期限切れになる証明書のみ
+    pub expiring_within: Option<Duration>,
+    /// 基準日時（None は現在時刻）
+    pub as_of: Option<Timestamp>,
+    /// entry_timestamp がこの日時以降の証明書のみ
+    pub logged_after: Option<TimeSpec>,
+    /// entry_timestamp がこの日時より前の証明書のみ
+    pub logged_before: Option<TimeSpec>,
+    /// not_before がこの日時以降の証明書のみ
+    pub issued_after: Option<TimeSpec>,
+    /// not_before がこの日時より前の証明書のみ
+    pub issued_before: Option<TimeSpec>,
+    /// common_name または識別名のいずれかが一致する証明書のみ
+    pub match_name: Option<Pattern>,
+    /// common_name または識別名のいずれかが一致する証明書を除外
+    pub exclude_name: Option<Pattern>,
+    /// issuer_name、発行者の O または CN が一致する証明書のみ
+    pub issuer: Option<Pattern>,
+    /// ワイルドカードの名前を含む証明書を除外
+    pub exclude_wildcards: bool,
+}
+
+impl Filters {
+    /// 絞り込み条件が指定されていないかどうか
+    pub fn is_empty(&self) -> bool {
+        self.validity.is_none()
+            && self.expiring_within.is_none()
+            && self.logged_after.is_none()
+            && self.logged_before.is_none()
+            && self.issued_after.is_none()
+            && self.issued_before.is_none()
+            && self.match_name.is_none()
+            && self.exclude_name.is_none()
+            && self.issuer.is_none()
+            && !self.exclude_wildcards
+    }
+
+    /// 基準日時（UTC）
+    pub fn reference_time(&self) -> NaiveDateTime {
+        self.as_of
+            .as_ref()
+            .map_or_else(|| Utc::now().naive_utc(), Timestamp::value)
+    }
+
+    /// 証明書が条件を満たすかどうか
+    pub fn matches(&self, crt: &Crt) -> bool {
+        self.matches_at(crt, self.reference_time())
+    }
+
+    /// 条件を満たす証明書のみを残す
+    pub fn apply(&self, crts: Vec<Crt>) -> Vec<Crt> {
+        let now = self.reference_time();
+        crts.into_iter()
+            .filter(|crt| self.matches_at(crt, now))
+            .collect()
+    }
+
+    /// 指定した基準日時で証明書が条件を満たすかどうか
+    pub fn matches_at(&self, crt: &Crt, now: NaiveDateTime) -> bool {
+        let not_before = crt.not_before.value();
+        let not_after = crt.not_after.value();
+
+        let validity = match self.validity {
+            Some(Validity::Valid) => not_before <= now && now <= not_after,
+            Some(Validity::Expired) => not_after < now,
+            None => true,
+        };
+
+        let expiring = match self.expiring_within {
+            Some(within) => {
+                let deadline = TimeDelta::from_std(within)
+                    .ok()
+                    .and_then(|delta| now.checked_add_signed(delta))
+                    .unwrap_or(NaiveDateTime::MAX);
+                now <= not_after && not_after <= deadline
+            }
+            None => true,
+        };
+
+        // entry_timestamp が null の証明書は記録日時の条件を満たさないものとして扱う
+        let logged = match (&self.logged_after, &self.logged_before) {
+            (None, None) => true,
+            (after, before) => crt.entry_timestamp.as_ref().is_some_and(|logged_at| {
+                in_range(logged_at.value(), after.as_ref(), before.as_ref(), now)
+            }),
+        };
+
+        let issued = in_range(
+            not_before,
+            self.issued_after.as_ref(),
+            self.issued_before.as_ref(),
+            now,
+        );
+
+        validity && expiring && logged && issued && self.matches_names(crt)
+    }
+
+    /// 名前・発行者の条件を満たすかどうか
+    fn matches_names(&self, crt: &Crt) -> bool {
+        let identities = crt.identities();
+        let names = || {
+            std::iter::once(crt.common_name.as_str())
+                .chain(identities.iter().map(|identity| identity.value.as_str()))
+        };
+
+        if let Some(pattern) = &self.match_name
+            && !names().any(|name| pattern.is_match(name))
+        {
+            return false;
+        }
+
+        if let Some(pattern) = &self.exclude_name
+            && names().any(|name| pattern.is_match(name))
+        {
+            return false;
+        }
+
+        if let Some(pattern) = &self.issuer {
+            let issuer = crt.issuer();
+            let matched = pattern.is_match(&crt.issuer_name)
+                || [issuer.organization(), issuer.common_name()]
+                    .into_iter()
+                    .flatten()
+                    .any(|value| pattern.is_match(value));
+            if !matched {
+                return false;
+            }
+        }
+
+        if self.exclude_wildcards
+            && (crt.common_name.starts_with(WILDCARD_PREFIX)
+                || identities.iter().any(Identity::is_wildcard))
+        {
+            return false;
+        }
+
+        true
+    }
+}
+
+/// 日時が [after, before) の範囲内かどうか
+fn in_range(
+    value: NaiveDateTime,
+    after: Option<&TimeSpec>,
+    before: Option<&TimeSpec>,
+    now: NaiveDateTime,
+) -> bool {
+    after.is_none_or(|after| after.resolve(now) <= value)
+        && before.is_none_or(|before| value < before.resolve(now))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn crt(not_before: &str, not_after: &str) -> Crt {
+        serde_json::from_value(json!({
+            "id": 1,
+            "common_name": "www.example.com",
+            "entry_timestamp": "2025-12-16T21:50:43.225",
+            "issuer_ca_id": 1,
+            "issuer_name": "C=US, O=Let's Encrypt, CN=R11",
+            "name_value": "www.example.com\n*.example.com",
+            "not_after": not_after,
+            "not_before": not_before,
+            "result_count": 1,
+            "serial_number": "00",
+        }))
+        .unwrap()
+    }
+
+    fn at(raw: &str) -> NaiveDateTime {
+        Timestamp::parse(raw).unwrap().value()
+    }
+
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    #[test]
+    fn empty_filters_match_everything() {
+        let filters = Filters::default();
+
+        assert!(filters.is_empty());
+        assert!(filters.matches_at(&crt("2020-01-01", "2020-02-01"), at("2026-01-01")));
+    }
+
+    #[test]
+    fn validity_is_evaluated_at_reference_time_inclusively() {
+        let crt = crt("2026-01-01T00:00:00", "2026-03-01T00:00:00");
+        let valid = Filters {
+            validity: Some(Validity::Valid),
+            ..Filters::default()
+        };
+        let expired = Filters {
+            validity: Some(Validity::Expired),
+            ..Filters::default()
+        };
+
+        assert!(!valid.matches_at(&crt, at("2025-12-31T23:59:59")));
+        assert!(valid.matches_at(&crt, at("2026-01-01T00:00:00")));
+        assert!(valid.matches_at(&crt, at("2026-03-01T00:00:00")));
+        assert!(!expired.matches_at(&crt, at("2026-03-01T00:00:00")));
+        assert!(expired.matches_at(&crt, at("2026-03-01T00:00:01")));
+    }
+
+    #[test]
+    fn expiring_within_excludes_expired_and_later_certificates() {
+        let filters = Filters {
+            expiring_within: Some(30 * DAY),
+            ..Filters::default()
+        };
+        let now = at("2026-02-01");
+
+        assert!(filters.matches_at(&crt("2026-01-01", "2026-02-20"), now));
+        assert!(filters.matches_at(&crt("2026-01-01", "2026-03-03"), now));
+        assert!(!filters.matches_at(&crt("2026-01-01", "2026-03-04"), now));
+        assert!(!filters.matches_at(&crt("2025-01-01", "2026-01-31"), now));
+    }
+
+    #[test]
+    fn as_of_replaces_current_time() {
+        let filters = Filters {
+            validity: Some(Validity::Valid),
+            as_of: Some(Timestamp::parse("2020-01-15").unwrap()),
+            ..Filters::default()
+        };
+
+        assert_eq!(filters.reference_time(), at("2020-01-15"));
+        assert!(filters.matches(&crt("2020-01-01", "2020-02-01")));
+        assert!(!filters.matches(&crt("2021-01-01", "2021-02-01")));
+    }
+
+    #[test]
+    fn all_conditions_must_match() {
+        let filters = Filters {
+            validity: Some(Validity::Valid),
+            expiring_within: Some(7 * DAY),
+            exclude_wildcards: true,
+            ..Filters::default()
+        };
+        let now = at("2026-02-01");
+
+        assert!(!filters.is_empty());
+        assert!(!filters.matches_at(&crt("2026-01-01", "2026-02-05"), now));
+
+        let without_wildcards = Filters {
+            exclude_wildcards: false,
+            ..filters.clone()
+        };
+        assert!(without_wildcards.matches_at(&crt("2026-01-01", "2026-02-05"), now));
+        assert!(!without_wildcards.matches_at(&crt("2026-01-01", "2026-03-01"), now));
+    }
+
+    #[test]
+    fn apply_keeps_matching_certificates_in_order() {
+        let filters = Filters {
+            validity: Some(Validity::Expired),
+            as_of: Some(Timestamp::parse("2026-01-01").unwrap()),
+            ..Filters::default()
+        };
+        let crts = vec![
+            crt("2025-01-01", "2025-06-01"),
+            crt("2025-06-01", "2026-06-01"),
+            crt("2024-01-01", "2024-06-01"),
+        ];
+
+        let kept = filters.apply(crts);
+
+        assert_eq!(
+            kept.iter()
+                .map(|crt| crt.not_after.raw())
+                .collect::<Vec<_>>(),
+            ["2025-06-01", "2024-06-01"]
+        );
+    }
+
+    fn logged_crt(entry_timestamp: Option<&str>, not_before: &str) -> Crt {
+        let mut crt = crt(not_before, "2027-01-01");
+        crt.entry_timestamp = entry_timestamp.map(|raw| Timestamp::parse(raw).unwrap());
+        crt
+    }
+
+    #[test]
+    fn logged_range_includes_after_and_excludes_before() {
+        let filters = Filters {
+            logged_after: Some(TimeSpec::parse("2026-01-01").unwrap()),
+            logged_before: Some(TimeSpec::parse("2026-02-01").unwrap()),
+            ..Filters::default()
+        };
+        let now = at("2026-06-01");
+
+        assert!(filters.matches_at(&logged_crt(Some("2026-01-01T00:00:00"), "2026-01-01"), now));
+        assert!(!filters.matches_at(&logged_crt(Some("2025-12-31T23:59:59"), "2026-01-01"), now));
+        assert!(!filters.matches_at(&logged_crt(Some("2026-02-01T00:00:00"), "2026-01-01"), now));
+        assert!(!filters.matches_at(&logged_crt(None, "2026-01-01"), now));
+    }
+
+    #[test]
+    fn issued_range_accepts_relative_specs() {
+        let filters = Filters {
+            issued_after: Some(TimeSpec::parse("7d").unwrap()),
+            ..Filters::default()
+        };
+        let now = at("2026-01-08");
+
+        assert!(filters.matches_at(&logged_crt(None, "2026-01-05"), now));
+        assert!(!filters.matches_at(&logged_crt(None, "2025-12-31"), now));
+    }
+
+    fn named_crt(common_name: &str, name_value: &str, issuer_name: &str) -> Crt {
+        let mut crt = crt("2026-01-01", "2027-01-01");
+        crt.common_name = common_name.to_string();
+        crt.name_value = name_value.to_string();
+        crt.issuer_name = issuer_name.to_string();
+        crt
+    }
+
+    #[test]
+    fn name_patterns_check_common_name_and_identities() {
+        let filters = Filters {
+            match_name: Some(Pattern::glob("*.example.com").unwrap()),
+            exclude_name: Some(Pattern::regex("staging").unwrap()),
+            ..Filters::default()
+        };
+        let issuer = "C=US, O=Let's Encrypt, CN=R11";
+
+        assert!(filters.matches(&named_crt(
+            "example.com",
+            "example.com\nwww.example.com",
+            issuer
+        )));
+        assert!(!filters.matches(&named_crt("example.com", "example.com", issuer)));
+        assert!(!filters.matches(&named_crt(
+            "www.example.com",
+            "www.example.com\nstaging.example.com",
+            issuer
+        )));
+    }
+
+    #[test]
+    fn issuer_pattern_checks_full_name_organization_and_common_name() {
+        let crt = named_crt(
+            "www.example.com",
+            "www.example.com",
+            "C=US, O=Let's Encrypt, CN=R11",
+        );
+
+        for pattern in [
+            Pattern::glob("let's encrypt").unwrap(),
+            Pattern::glob("r1?").unwrap(),
+            Pattern::regex("C=US").unwrap(),
+        ] {
+            let filters = Filters {
+                issuer: Some(pattern),
+                ..Filters::default()
+            };
+            assert!(filters.matches(&crt));
+        }
+
+        let filters = Filters {
+            issuer: Some(Pattern::glob("Sectigo*").unwrap()),
+            ..Filters::default()
+        };
+        assert!(!filters.matches(&crt));
+    }
+
+    #[test]
+    fn exclude_wildcards_checks_common_name_and_identities() {
+        let filters = Filters {
+            exclude_wildcards: true,
+            ..Filters::default()
+        };
+        let issuer = "C=US, O=Let's Encrypt, CN=R11";
+
+        assert!(filters.matches(&named_crt("www.example.com", "www.example.com", issuer)));
+        assert!(!filters.matches(&named_crt("*.example.com", "www.example.com", issuer)));
+        assert!(!filters.matches(&named_crt(
+            "example.com",
+            "example.com\n*.example.com",
+            issuer
+        )));
+    }
+}
@@ -0,0 +1,215 @@
+use crate::crt::Crt;
+use crate::timestamp::Timestamp;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+// 月別集計のキーの形式
+const MONTH_FORMAT: &str = "%Y-%m";
+
+// エラーメッセージ
+const ERROR_UNKNOWN_GROUP_BY: &str =
+    "Unknown group: {} (expected issuer_name, common_name, issuer_ca_id or month)";
+
+/// 集計の単位
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GroupBy {
+    IssuerName,
+    CommonName,
+    IssuerCaId,
+    /// not_before の月（YYYY-MM）
+    Month,
+}
+
+impl GroupBy {
+    /// 集計の単位の名前
+    pub fn name(&self) -> &'static str {
+        match self {
+            GroupBy::IssuerName => "issuer_name",
+            GroupBy::CommonName => "common_name",
+            GroupBy::IssuerCaId => "issuer_ca_id",
+            GroupBy::Month => "month",
+        }
+    }
+
+    /// 証明書の集計キー
+    pub fn key(&self, crt: &Crt) -> String {
+        match self {
+            GroupBy::IssuerName => crt.issuer_name.clone(),
+            GroupBy::CommonName => crt.common_name.clone(),
+            GroupBy::IssuerCaId => crt.issuer_ca_id.to_string(),
+            GroupBy::Month => crt.not_before.value().format(MONTH_FORMAT).to_string(),
+        }
+    }
+
+    /// 集計キーを比較（issuer_ca_id は数値として比較）
+    fn compare_keys(&self, a: &str, b: &str) -> Ordering {
+        match (self, a.parse::<i64>(), b.parse::<i64>()) {
+            (GroupBy::IssuerCaId, Ok(a), Ok(b)) => a.cmp(&b),
+            _ => a.cmp(b),
+        }
+    }
+}
+
+impl std::str::FromStr for GroupBy {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "issuer_name" => Ok(GroupBy::IssuerName),
+            "common_name" => Ok(GroupBy::CommonName),
+            "issuer_ca_id" => Ok(GroupBy::IssuerCaId),
+            "month" => Ok(GroupBy::Month),
+            _ => Err(ERROR_UNKNOWN_GROUP_BY.replace("{}", s)),
+        }
+    }
+}
+
+/// 集計結果の 1 行
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Group {
+    pub key: String,
+    pub count: usize,
+    pub earliest_not_before: Timestamp,
+    pub latest_not_after: Timestamp,
+}
+
+/// 証明書を集計キーごとにまとめる（キーの昇順）
+pub fn group_crts(crts: &[Crt], group_by: GroupBy) -> Vec<Group> {
+    let mut groups: HashMap<String, Group> = HashMap::new();
+
+    for crt in crts {
+        let key = group_by.key(crt);
+        match groups.get_mut(&key) {
+            Some(group) => {
+                group.count += 1;
+                if crt.not_before < group.earliest_not_before {
+                    group.earliest_not_before = crt.not_before.clone();
+                }
+                if crt.not_after > group.latest_not_after {
+                    group.latest_not_after = crt.not_after.clone();
+                }
+            }
+            None => {
+                groups.insert(
+                    key.clone(),
+                    Group {
+                        key,
+                        count: 1,
+                        earliest_not_before: crt.not_before.clone(),
+                        latest_not_after: crt.not_after.clone(),
+                    },
+                );
+            }
+        }
+    }
+
+    let mut groups: Vec<Group> = groups.into_values().collect();
+    groups.sort_by(|a, b| group_by.compare_keys(&a.key, &b.key));
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn crt(
+        common_name: &str,
+        issuer_ca_id: i64,
+        issuer_name: &str,
+        not_before: &str,
+        not_after: &str,
+    ) -> Crt {
+        serde_json::from_value(json!({
+            "id": 1,
+            "common_name": common_name,
+            "entry_timestamp": "2025-12-16T21:50:43.225",
+            "issuer_ca_id": issuer_ca_id,
+            "issuer_name": issuer_name,
+            "name_value": common_name,
+            "not_after": not_after,
+            "not_before": not_before,
+            "result_count": 1,
+            "serial_number": "00",
+        }))
+        .unwrap()
+    }
+
+    const LE: &str = "C=US, O=Let's Encrypt, CN=R11";
+    const SECTIGO: &str = "C=GB, O=Sectigo Limited, CN=Sectigo CA";
+
+    fn sample() -> Vec<Crt> {
+        vec![
+            crt("www.example.com", 9, SECTIGO, "2026-01-20", "2026-04-20"),
+            crt("example.com", 10, LE, "2025-12-15", "2026-03-15"),
+            crt("www.example.com", 10, LE, "2026-01-05", "2026-04-05"),
+            crt("api.example.com", 10, LE, "2026-01-10", "2026-02-10"),
+        ]
+    }
+
+    fn summary(groups: &[Group]) -> Vec<(&str, usize, &str, &str)> {
+        groups
+            .iter()
+            .map(|group| {
+                (
+                    group.key.as_str(),
+                    group.count,
+                    group.earliest_not_before.raw(),
+                    group.latest_not_after.raw(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn groups_by_month_of_not_before() {
+        assert_eq!(
+            summary(&group_crts(&sample(), GroupBy::Month)),
+            [
+                ("2025-12", 1, "2025-12-15", "2026-03-15"),
+                ("2026-01", 3, "2026-01-05", "2026-04-20"),
+            ]
+        );
+    }
+
+    #[test]
+    fn groups_by_issuer_name() {
+        assert_eq!(
+            summary(&group_crts(&sample(), GroupBy::IssuerName)),
+            [
+                (SECTIGO, 1, "2026-01-20", "2026-04-20"),
+                (LE, 3, "2025-12-15", "2026-04-05"),
+            ]
+        );
+    }
+
+    #[test]
+    fn groups_by_common_name() {
+        assert_eq!(
+            summary(&group_crts(&sample(), GroupBy::CommonName)),
+            [
+                ("api.example.com", 1, "2026-01-10", "2026-02-10"),
+                ("example.com", 1, "2025-12-15", "2026-03-15"),
+                ("www.example.com", 2, "2026-01-05", "2026-04-20"),
+            ]
+        );
+    }
+
+    #[test]
+    fn sorts_issuer_ca_ids_numerically() {
+        let keys: Vec<String> = group_crts(&sample(), GroupBy::IssuerCaId)
+            .into_iter()
+            .map(|group| group.key)
+            .collect();
+
+        assert_eq!(keys, ["9", "10"]);
+    }
+
+    #[test]
+    fn parses_group_names() {
+        assert_eq!("month".parse::<GroupBy>(), Ok(GroupBy::Month));
+        assert_eq!("issuer_name".parse::<GroupBy>(), Ok(GroupBy::IssuerName));
+        assert!("issuer".parse::<GroupBy>().is_err());
+    }
+}
@@ -0,0 +1,136 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+use std::net::IpAddr;
+
+// ワイルドカードの接頭辞
+const WILDCARD_PREFIX: &str = "*.";
+
+/// 識別名の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IdentityKind {
+    Dns,
+    Ip,
+    Email,
+    Other,
+}
+
+/// 証明書に含まれる識別名（name_value の 1 行）
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Identity {
+    pub kind: IdentityKind,
+    pub value: String,
+}
+
+impl Identity {
+    /// 1 つの識別名を解析（空行は None）
+    pub fn parse(raw: &str) -> Option<Self> {
+        let value = raw.trim();
+        if value.is_empty() {
+            return None;
+        }
+
+        let kind = classify(value);
+        let value = match kind {
+            IdentityKind::Dns | IdentityKind::Email => value.to_ascii_lowercase(),
+            IdentityKind::Ip | IdentityKind::Other => value.to_string(),
+        };
+
+        Some(Self { kind, value })
+    }
+
+    /// ワイルドカード（*.example.com）かどうか
+    pub fn is_wildcard(&self) -> bool {
+        self.kind == IdentityKind::Dns && self.value.starts_with(WILDCARD_PREFIX)
+    }
+}
+
+impl fmt::Display for Identity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.value)
+    }
+}
+
+/// 改行区切りの name_value を重複のない識別名の一覧に変換（出現順を維持）
+pub fn parse_identities(name_value: &str) -> Vec<Identity> {
+    let mut seen = HashSet::new();
+
+    name_value
+        .lines()
+        .filter_map(Identity::parse)
+        .filter(|identity| seen.insert(identity.clone()))
+        .collect()
+}
+
+/// 識別名の種類を判定
+fn classify(value: &str) -> IdentityKind {
+    if value.parse::<IpAddr>().is_ok() {
+        IdentityKind::Ip
+    } else if value.contains('@') {
+        IdentityKind::Email
+    } else if value
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_' | '*'))
+    {
+        IdentityKind::Dns
+    } else {
+        IdentityKind::Other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(name_value: &str) -> Vec<(IdentityKind, String)> {
+        parse_identities(name_value)
+            .into_iter()
+            .map(|identity| (identity.kind, identity.value))
+            .collect()
+    }
+
+    #[test]
+    fn classifies_dns_ip_and_email() {
+        assert_eq!(
+            kinds("www.example.com\n192.0.2.1\n2001:db8::1\nAdmin@Example.com"),
+            [
+                (IdentityKind::Dns, "www.example.com".to_string()),
+                (IdentityKind::Ip, "192.0.2.1".to_string()),
+                (IdentityKind::Ip, "2001:db8::1".to_string()),
+                (IdentityKind::Email, "admin@example.com".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_quoted_and_free_text_values_as_other() {
+        assert_eq!(
+            kinds("\"Example, Inc.\"\nExample Org\n'quoted'"),
+            [
+                (IdentityKind::Other, "\"Example, Inc.\"".to_string()),
+                (IdentityKind::Other, "Example Org".to_string()),
+                (IdentityKind::Other, "'quoted'".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn dedupes_after_trimming_and_lowercasing() {
+        assert_eq!(
+            kinds("Example.com\n\n  example.com \nEXAMPLE.COM\r\nwww.example.com"),
+            [
+                (IdentityKind::Dns, "example.com".to_string()),
+                (IdentityKind::Dns, "www.example.com".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn detects_wildcard_dns_names_only() {
+        assert!(Identity::parse("*.example.com").unwrap().is_wildcard());
+        assert!(!Identity::parse("www.example.com").unwrap().is_wildcard());
+        assert!(!Identity::parse("*.example.com corp").unwrap().is_wildcard());
+        assert!(Identity::parse("   ").is_none());
+    }
+}
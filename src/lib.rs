@@ -0,0 +1,72 @@
+//! crt.sh の証明書透明性ログを検索するためのライブラリ
+//!
+//! ```no_run
+//! use rs_crtsh::{Config, CrtShClient, Query, SearchField};
+//!
+//! let query = Query::builder()
+//!     .search(SearchField::Q, "example.com")
+//!     .build()?;
+//! let client = CrtShClient::new(Config::default())?;
+//!
+//! for crt in client.search(&query)? {
+//!     println!("{} {}", crt.id, crt.common_name);
+//! }
+//! # Ok::<(), rs_crtsh::Error>(())
+//! ```
+
+#[cfg(feature = "async")]
+mod async_client;
+mod batch;
+mod cache;
+mod client;
+mod crt;
+mod dedupe;
+mod diff;
+mod dn;
+mod error;
+mod filter;
+mod group;
+mod identity;
+mod pattern;
+mod query;
+mod retry;
+mod search_term;
+mod sort;
+mod stats;
+mod subdomain;
+mod timespec;
+mod timestamp;
+mod transport;
+mod watch;
+mod webhook;
+
+#[cfg(feature = "async")]
+pub use async_client::AsyncCrtShClient;
+pub use cache::{
+    Cache, CacheMode, DEFAULT_CACHE_MAX_SIZE_MB, DEFAULT_CACHE_TTL_SECS, cache_key,
+    default_cache_dir,
+};
+pub use client::{
+    Config, ConfigPreset, CrtShClient, DEFAULT_CONCURRENCY, DEFAULT_REQUEST_DELAY,
+    DEFAULT_RETRY_COUNT, DEFAULT_RETRY_DELAY, DEFAULT_RETRY_MAX_DELAY, DEFAULT_TIMEOUT_SECS,
+    Response, ResponseInfo, TimingInfo, load_config_file, load_preset, parse_crts,
+};
+pub use crt::Crt;
+pub use dedupe::dedupe_crts;
+pub use diff::{Diff, diff_crts};
+pub use dn::{DistinguishedName, DnComponent};
+pub use error::{Error, Result};
+pub use filter::{Filters, Validity};
+pub use group::{Group, GroupBy, group_crts};
+pub use identity::{Identity, IdentityKind, parse_identities};
+pub use pattern::Pattern;
+pub use query::{MatchMode, Query, QueryBuilder, SearchField};
+pub use retry::{RetryEvent, RetryPolicy, retry_after};
+pub use search_term::{SearchTerm, TermKind};
+pub use sort::{SortKey, SortOrder};
+pub use stats::{Stats, UpcomingExpiry, ValidityStats};
+pub use subdomain::{SubdomainOptions, collect_subdomains};
+pub use timespec::{TimeSpec, parse_duration};
+pub use timestamp::Timestamp;
+pub use watch::{WatchOptions, WatchReport, WatchState, Watcher, default_state_path};
+pub use webhook::{Delivery, Webhook, WebhookKind, notify_webhooks};
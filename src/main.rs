@@ -1,132 +1,663 @@
-mod client;
-mod crt;
+mod output;
 
-use crate::client::{execute_request, load_config_file, Config, Format, COLUMN_COMMON_NAME, COLUMN_ENTRY_TIMESTAMP, COLUMN_ID, COLUMN_ISSUER_CA_ID, COLUMN_ISSUER_NAME, COLUMN_NAME_VALUE, COLUMN_NOT_AFTER, COLUMN_NOT_BEFORE, COLUMN_RESULT_COUNT, COLUMN_SERIAL_NUMBER};
-use crate::client::{DEFAULT_RETRY_COUNT, DEFAULT_RETRY_DELAY, DEFAULT_TIMEOUT_SECS, DEFAULT_FORMAT};
-use clap::Parser;
-use std::error::Error;
+use crate::output::{execute_diff, execute_request, execute_subdomains, execute_watch, DiffOptions, DiffSource, Format, NameValueFormat, Options, OptionsPreset, DEFAULT_MAX_ROWS, DIFF_CACHE_PREFIX, COLUMN_COMMON_NAME, COLUMN_ENTRY_TIMESTAMP, COLUMN_ID, COLUMN_ISSUER_CA_ID, COLUMN_ISSUER_NAME, COLUMN_LOG_ENTRIES, COLUMN_NAME_VALUE, COLUMN_NOT_AFTER, COLUMN_NOT_BEFORE, COLUMN_QUERY, COLUMN_RESULT_COUNT, COLUMN_SERIAL_NUMBER};
+use rs_crtsh::{DEFAULT_RETRY_COUNT, DEFAULT_RETRY_DELAY, DEFAULT_RETRY_MAX_DELAY, DEFAULT_TIMEOUT_SECS, DEFAULT_CONCURRENCY, DEFAULT_REQUEST_DELAY};
+use rs_crtsh::{load_preset, CacheMode, Error, GroupBy, MatchMode, Pattern, Query, QueryBuilder, Result, SearchField, SortKey, SubdomainOptions, TimeSpec, Timestamp, Validity, WatchOptions, Webhook, default_cache_dir, default_state_path, parse_duration};
+use clap::builder::RangedU64ValueParser;
+use clap::{Parser, Subcommand};
+use std::fs;
+use std::io;
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
+use std::process::ExitCode;
 
-const API_URL: &str = "https://crt.sh/?q=example.com&output=json";
-const ERROR_MISSING_URL: &str = "hostname is required. Use --hostname option or specify in config file.";
+const ERROR_GROUP_BY_FORMAT: &str = "--group-by can only be used with the table or csv format";
+const ERROR_READ_INPUT_FILE: &str = "Failed to read input file '{1}': {2}";
+const ERROR_EMPTY_INPUT_FILE: &str = "No hostnames found in input file '{}'";
+const ERROR_DIFF_FORMAT: &str = "diff supports the table and json formats (or --summary)";
+const ERROR_READ_TEMPLATE: &str = "Failed to read webhook template '{1}': {2}";
+const ERROR_NO_STATE_PATH: &str = "Could not determine a watch state file. Use --state to specify one.";
+const STDIN_PATH: &str = "-";
+const DEFAULT_WATCH_INTERVAL: &str = "1h";
+const URL_SCHEME_SEPARATOR: &str = "://";
+const TEMPLATE_FILE_PREFIX: &str = "@";
+const ERROR_MISSING_URL: &str = "A search parameter is required. Use --hostname (or another search option), --input, or specify url in config file.";
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Path to a configuration file
-    #[arg(short, long)]
+    #[arg(short, long, global = true)]
     config: Option<String>,
 
     /// List of column names to display in a table
-    #[arg(long = "column_name", action = clap::ArgAction::Append)]
+    #[arg(long = "column_name", action = clap::ArgAction::Append, global = true)]
     column_names: Vec<String>,
 
-    /// Output format (table, csv, or raw)
-    #[arg(short, long, default_value = DEFAULT_FORMAT)]
-    format: Option<String>,
+    /// Output format (table, csv, json, jsonl, stats or raw)
+    #[arg(short, long, global = true)]
+    format: Option<Format>,
 
-    /// Hostname to search for
-    #[arg(long)]
-    hostname: Option<String>,
+    /// How to render name_value (joined, explode or json)
+    #[arg(long, value_parser = ["joined", "explode", "json"], global = true)]
+    name_value_format: Option<String>,
+
+    /// Separator used to join identities in name_value (supports \n and \t)
+    #[arg(long, global = true)]
+    name_value_separator: Option<String>,
+
+    /// Hostname to search for (crt.sh `q` parameter, repeatable for batch queries)
+    #[arg(long, action = clap::ArgAction::Append, global = true)]
+    hostname: Vec<String>,
+
+    /// File with one hostname per line to search in batch (`-` reads from stdin)
+    #[arg(long, global = true)]
+    input_file: Option<String>,
+
+    /// Render a saved crt.sh JSON response instead of querying crt.sh (`-` reads from stdin)
+    #[arg(long, conflicts_with = "input_file", global = true)]
+    input: Option<String>,
+
+    /// Maximum number of batch queries running at the same time (at least 1)
+    #[arg(long, default_value_t = DEFAULT_CONCURRENCY, value_parser = RangedU64ValueParser::<usize>::new().range(1..), global = true)]
+    concurrency: usize,
+
+    /// Minimum delay in seconds between the start of batch queries
+    #[arg(long, default_value_t = DEFAULT_REQUEST_DELAY, global = true)]
+    request_delay: f64,
+
+    /// Do not read or write the response cache
+    #[arg(long, default_value_t = false, conflicts_with_all = ["refresh", "offline"], global = true)]
+    no_cache: bool,
+
+    /// Ignore cached responses, fetch from crt.sh and update the cache
+    #[arg(long, default_value_t = false, conflicts_with = "offline", global = true)]
+    refresh: bool,
+
+    /// Serve responses only from the cache (including expired entries) without network access
+    #[arg(long, default_value_t = false, global = true)]
+    offline: bool,
+
+    /// Directory for cached responses (default: $XDG_CACHE_HOME/rs-crtsh or ~/.cache/rs-crtsh)
+    #[arg(long, global = true)]
+    cache_dir: Option<PathBuf>,
+
+    /// How long cached responses stay fresh, e.g. 30m, 1h or 7d (default: 1h)
+    #[arg(long, global = true)]
+    cache_ttl: Option<String>,
+
+    /// Maximum total size of the cache in MB (oldest entries are removed first)
+    #[arg(long, global = true)]
+    cache_max_size: Option<u64>,
+
+    /// Search by identity (crt.sh `identity` parameter)
+    #[arg(long, global = true)]
+    identity: Option<String>,
+
+    /// Search by Subject Common Name (crt.sh `CN` parameter)
+    #[arg(long, global = true)]
+    cn: Option<String>,
+
+    /// Search by Subject Organization (crt.sh `O` parameter)
+    #[arg(long, global = true)]
+    org: Option<String>,
+
+    /// Search by certificate serial number
+    #[arg(long, global = true)]
+    serial: Option<String>,
+
+    /// Search by certificate SHA-1 fingerprint
+    #[arg(long, global = true)]
+    sha1: Option<String>,
+
+    /// Search by certificate SHA-256 fingerprint
+    #[arg(long, global = true)]
+    sha256: Option<String>,
+
+    /// Search by SHA-256 of the SubjectPublicKeyInfo
+    #[arg(long, global = true)]
+    spkisha256: Option<String>,
+
+    /// Search by issuer CA ID
+    #[arg(long, global = true)]
+    caid: Option<String>,
+
+    /// Exclude expired certificates
+    #[arg(long, default_value_t = false, global = true)]
+    exclude_expired: bool,
+
+    /// Deduplicate precertificate/certificate pairs
+    #[arg(long, default_value_t = false, global = true)]
+    deduplicate: bool,
+
+    /// Collapse entries sharing serial_number and issuer_ca_id, keeping the earliest logged one
+    #[arg(long, default_value_t = false, global = true)]
+    dedupe: bool,
+
+    /// Match mode for text searches (=, LIKE, ILIKE or FTS)
+    #[arg(long = "match", global = true)]
+    match_mode: Option<MatchMode>,
+
+    /// Sort by a column, optionally with :asc or :desc (repeatable, e.g. --sort not_after:desc)
+    #[arg(long = "sort", action = clap::ArgAction::Append, global = true)]
+    sort_keys: Vec<String>,
+
+    /// Maximum number of certificates to output
+    #[arg(long, global = true)]
+    limit: Option<usize>,
+
+    /// Number of certificates to skip before output
+    #[arg(long, default_value_t = 0, global = true)]
+    offset: usize,
+
+    /// Show the table page by page with this many rows per page (only when stdout is a terminal)
+    #[arg(long, global = true)]
+    page_size: Option<NonZeroUsize>,
+
+    /// Refuse to output more rows than this in any format except stats (0 disables the guard)
+    #[arg(long, default_value_t = DEFAULT_MAX_ROWS, global = true)]
+    max_rows: usize,
+
+    /// Output the rows even if they exceed --max-rows
+    #[arg(long, default_value_t = false, global = true)]
+    force: bool,
+
+    /// Aggregate rows by issuer_name, common_name, issuer_ca_id or month (table and csv only)
+    #[arg(long, global = true)]
+    group_by: Option<GroupBy>,
+
+    /// Only show certificates that are currently valid
+    #[arg(long, default_value_t = false, conflicts_with = "only_expired", global = true)]
+    only_valid: bool,
+
+    /// Only show certificates that have expired
+    #[arg(long, default_value_t = false, conflicts_with = "expiring_within", global = true)]
+    only_expired: bool,
+
+    /// Only show certificates expiring within the given duration (e.g. 30d, 12h, 2w)
+    #[arg(long, global = true)]
+    expiring_within: Option<String>,
+
+    /// Evaluate validity as of this date instead of now (e.g. 2025-01-01)
+    #[arg(long, global = true)]
+    as_of: Option<String>,
+
+    /// Only show certificates logged at or after this date or duration ago (e.g. 2025-01-01, 7d)
+    #[arg(long, global = true)]
+    logged_after: Option<String>,
+
+    /// Only show certificates logged before this date or duration ago
+    #[arg(long, global = true)]
+    logged_before: Option<String>,
+
+    /// Only show certificates whose not_before is at or after this date or duration ago
+    #[arg(long, global = true)]
+    issued_after: Option<String>,
+
+    /// Only show certificates whose not_before is before this date or duration ago
+    #[arg(long, global = true)]
+    issued_before: Option<String>,
+
+    /// Only show certificates whose common name or any identity matches this regex
+    #[arg(long, global = true)]
+    match_name: Option<String>,
+
+    /// Hide certificates whose common name or any identity matches this regex
+    #[arg(long, global = true)]
+    exclude_name: Option<String>,
+
+    /// Only show certificates whose issuer name, organization or CN matches this glob (e.g. "Let's Encrypt", "*Sectigo*")
+    #[arg(long, global = true)]
+    issuer: Option<String>,
+
+    /// Hide certificates that contain a wildcard name
+    #[arg(long, default_value_t = false, global = true)]
+    exclude_wildcards: bool,
 
     /// Preset name from a configuration file
-    #[arg(long)]
+    #[arg(long, global = true)]
     preset: Option<String>,
 
     /// Number of retry attempts
-    #[arg(long, default_value_t = DEFAULT_RETRY_COUNT)]
+    #[arg(long, default_value_t = DEFAULT_RETRY_COUNT, global = true)]
     retry: u32,
 
     /// Delay between retries in seconds
-    #[arg(long, default_value_t = DEFAULT_RETRY_DELAY)]
+    #[arg(long, default_value_t = DEFAULT_RETRY_DELAY, global = true)]
     retry_delay: f64,
 
+    /// Maximum backoff delay between retries in seconds (a longer Retry-After stops retrying)
+    #[arg(long, default_value_t = DEFAULT_RETRY_MAX_DELAY, global = true)]
+    retry_max_delay: f64,
+
+    /// Randomise each retry delay between zero and the backoff delay
+    #[arg(long, default_value_t = false, global = true)]
+    retry_jitter: bool,
+
+    /// Give up retrying once this many seconds have elapsed in total
+    #[arg(long, global = true)]
+    retry_budget: Option<f64>,
+
     /// Timeout duration in seconds
-    #[arg(short, long, default_value_t = DEFAULT_TIMEOUT_SECS)]
+    #[arg(short, long, default_value_t = DEFAULT_TIMEOUT_SECS, global = true)]
     timeout: u64,
 
     /// Display timing information
-    #[arg(long, default_value_t = false)]
+    #[arg(long, default_value_t = false, global = true)]
     timing: bool,
 
     /// Enable verbose output
-    #[arg(short, long, default_value_t = false)]
+    #[arg(short, long, default_value_t = false, global = true)]
     verbose: bool,
 }
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let args = Args::parse();
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// List unique subdomains found in the matching certificates
+    Subdomains(SubdomainsArgs),
+    /// Re-run the search on an interval and print only certificates not seen before
+    Watch(WatchArgs),
+    /// Compare two saved result sets and report added and removed certificates, identities and issuers
+    Diff(DiffArgs),
+}
+
+#[derive(clap::Args, Debug)]
+struct SubdomainsArgs {
+    /// Keep the leading `*.` of wildcard names instead of stripping it
+    #[arg(long, default_value_t = false)]
+    keep_wildcards: bool,
+
+    /// Include names outside the searched domain
+    #[arg(long, default_value_t = false)]
+    no_scope: bool,
+}
+
+#[derive(clap::Args, Debug)]
+struct WatchArgs {
+    /// Interval between searches, e.g. 30m, 1h or 1d
+    #[arg(long, default_value = DEFAULT_WATCH_INTERVAL)]
+    interval: String,
+
+    /// File that stores the crt.sh IDs already seen (default: one file per search under ~/.local/state/rs-crtsh/watch)
+    #[arg(long)]
+    state: Option<PathBuf>,
+
+    /// Search once, print new certificates and exit (for cron or CI)
+    #[arg(long, default_value_t = false)]
+    once: bool,
+
+    /// Send new certificates to a webhook as `[generic|slack|teams=]URL` (repeatable)
+    #[arg(long, action = clap::ArgAction::Append)]
+    webhook: Vec<String>,
+
+    /// Template for the webhook body (generic) or message text (slack, teams); `@FILE` reads it from a file
+    #[arg(long)]
+    webhook_template: Option<String>,
+
+    /// Print the webhook payloads instead of sending them
+    #[arg(long, default_value_t = false)]
+    dry_run: bool,
+
+    /// Record the current results as seen without printing or notifying, then exit
+    #[arg(long, default_value_t = false)]
+    baseline: bool,
+}
+
+#[derive(clap::Args, Debug)]
+struct DiffArgs {
+    /// Older result set: a crt.sh JSON file, `-` for stdin, or `cache:<hostname|url>` for a cached response
+    old: String,
+
+    /// Newer result set (same forms as OLD)
+    new: String,
+
+    /// Print a compact summary of counts and changed names instead of tables
+    #[arg(long, default_value_t = false)]
+    summary: bool,
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::from(e.exit_code())
+        }
+    }
+}
+
+/// 引数を解析して検索を実行
+fn run() -> Result<()> {
+    let mut args = Args::parse();
+    let command = args.command.take();
 
     // 設定ファイルの読み込み
-    let mut config = load_config_if_specified(&args)?;
+    let mut options = load_config_if_specified(&args)?;
 
     // コマンドライン引数で設定ファイルの値をオーバーライド
-    apply_args_to_config(&mut config, args);
+    apply_args_to_config(&mut options, &args)?;
 
-    // URLが設定されていない場合はエラー
-    validate_config(&config)?;
+    // URLが設定されていない場合はエラー（diff は保存済みの結果を比較するため不要）
+    validate_config(&options, !matches!(command, Some(Command::Diff(_))))?;
 
     // HTTP リクエスト実行
-    execute_request(config)?;
+    match command {
+        Some(Command::Subdomains(subdomains_args)) => {
+            let subdomain_options = build_subdomain_options(&options, &subdomains_args);
+            execute_subdomains(options, &subdomain_options)?;
+        }
+        Some(Command::Watch(watch_args)) => {
+            let watch_options = build_watch_options(&options, &watch_args)?;
+            execute_watch(options, watch_options)?;
+        }
+        Some(Command::Diff(diff_args)) => {
+            let diff_options = build_diff_options(&options, &args, &diff_args)?;
+            execute_diff(options, &diff_options)?;
+        }
+        None => execute_request(options)?,
+    }
 
     Ok(())
 }
 
 /// 設定ファイルが指定されている場合に読み込む
-fn load_config_if_specified(args: &Args) -> Result<Config, Box<dyn Error>> {
+fn load_config_if_specified(args: &Args) -> Result<Options> {
     match &args.config {
-        Some(config_path) => load_config_file(config_path, args.preset.as_deref()),
-        None => Ok(Config::default()),
+        Some(config_path) => {
+            load_preset::<OptionsPreset>(config_path, args.preset.as_deref()).map(Options::from)
+        }
+        None => Ok(Options::default()),
+    }
+}
+
+/// サブドメイン列挙のオプションを作成（既定では検索したドメイン配下に限定）
+fn build_subdomain_options(options: &Options, args: &SubdomainsArgs) -> SubdomainOptions {
+    let domains = if args.no_scope {
+        Vec::new()
+    } else {
+        options
+            .query
+            .iter()
+            .chain(&options.queries)
+            .filter_map(|query| query.term().base_domain())
+            .collect()
+    };
+
+    SubdomainOptions {
+        keep_wildcards: args.keep_wildcards,
+        domains,
+    }
+}
+
+/// 監視のオプションを作成
+fn build_watch_options(options: &Options, args: &WatchArgs) -> Result<WatchOptions> {
+    let state_path = args
+        .state
+        .clone()
+        .or_else(|| default_state_path(&watch_targets(options)))
+        .ok_or_else(|| Error::Config(ERROR_NO_STATE_PATH.to_string()))?;
+
+    let template = args
+        .webhook_template
+        .as_deref()
+        .map(read_webhook_template)
+        .transpose()?;
+
+    let mut webhooks = options.webhooks.clone();
+    for raw in &args.webhook {
+        let mut webhook = Webhook::parse(raw)?;
+        webhook.template = template.clone();
+        webhooks.push(webhook);
+    }
+
+    Ok(WatchOptions {
+        interval: parse_duration(&args.interval)?,
+        state_path,
+        once: args.once,
+        webhooks,
+        dry_run: args.dry_run,
+        baseline: args.baseline,
+        label: watch_label(options),
+    })
+}
+
+/// 監視対象を識別する URL の一覧（保存済みの結果を監視する場合は入力ファイル）
+fn watch_targets(options: &Options) -> Vec<String> {
+    match &options.input {
+        Some(input) => vec![input.clone()],
+        None if options.queries.is_empty() => vec![options.url.clone()],
+        None => options.queries.iter().map(Query::to_url).collect(),
+    }
+}
+
+/// 通知に表示する監視対象（検索語、一括検索の場合は全検索語）
+fn watch_label(options: &Options) -> String {
+    let terms: Vec<&str> = options
+        .query
+        .iter()
+        .chain(&options.queries)
+        .map(|query| query.term().as_str())
+        .collect();
+
+    match (&options.input, terms.is_empty()) {
+        (Some(input), _) => input.clone(),
+        (None, true) => options.url.clone(),
+        (None, false) => terms.join(", "),
+    }
+}
+
+/// Webhook のテンプレートを取得（`@` で始まる場合はファイルから読み込む）
+fn read_webhook_template(raw: &str) -> Result<String> {
+    let Some(path) = raw.strip_prefix(TEMPLATE_FILE_PREFIX) else {
+        return Ok(raw.to_string());
+    };
+
+    fs::read_to_string(path).map_err(|e| {
+        Error::Config(
+            ERROR_READ_TEMPLATE
+                .replace("{1}", path)
+                .replace("{2}", &e.to_string()),
+        )
+    })
+}
+
+/// 差分のオプションを作成
+fn build_diff_options(options: &Options, args: &Args, diff_args: &DiffArgs) -> Result<DiffOptions> {
+    if !diff_args.summary && !matches!(options.format, Format::Table | Format::Json) {
+        return Err(Error::Config(ERROR_DIFF_FORMAT.to_string()));
     }
+
+    Ok(DiffOptions {
+        old: parse_diff_source(args, &diff_args.old)?,
+        new: parse_diff_source(args, &diff_args.new)?,
+        summary: diff_args.summary,
+    })
+}
+
+/// 差分の比較対象を解析（`cache:` のホスト名は検索オプションと合わせて URL に変換）
+fn parse_diff_source(args: &Args, raw: &str) -> Result<DiffSource> {
+    let Some(target) = raw.strip_prefix(DIFF_CACHE_PREFIX) else {
+        return Ok(DiffSource::File(raw.to_string()));
+    };
+
+    if target.contains(URL_SCHEME_SEPARATOR) {
+        return Ok(DiffSource::Cache(target.to_string()));
+    }
+
+    let query = build_query(args, Some(&target.to_string())).build()?;
+    Ok(DiffSource::Cache(query.to_url()))
 }
 
 /// 設定の有効性を検証
-fn validate_config(config: &Config) -> Result<(), Box<dyn Error>> {
-    if config.url.is_empty() {
-        return Err(ERROR_MISSING_URL.into());
+fn validate_config(options: &Options, needs_search: bool) -> Result<()> {
+    if needs_search && options.url.is_empty() && options.queries.is_empty() && options.input.is_none() {
+        return Err(Error::Config(ERROR_MISSING_URL.to_string()));
     }
+
+    if options.group_by.is_some() && !matches!(options.format, Format::Csv | Format::Table) {
+        return Err(Error::Config(ERROR_GROUP_BY_FORMAT.to_string()));
+    }
+
     Ok(())
 }
 
 /// コマンドライン引数を設定に反映
-fn apply_args_to_config(config: &mut Config, args: Args) {
-    // apply_auth_config(config, &args);
-    // apply_data_config(config, &args);
-    apply_request_config(config, &args);
-    // apply_proxy_config(config, &args);
-    apply_output_config(config, &args);
-    apply_retry_config(config, &args);
-    apply_flags(config, &args);
+fn apply_args_to_config(options: &mut Options, args: &Args) -> Result<()> {
+    // apply_auth_config(options, args);
+    // apply_data_config(options, args);
+    apply_request_config(options, args)?;
+    // apply_proxy_config(options, args);
+    apply_output_config(options, args)?;
+    apply_filter_config(options, args)?;
+    apply_retry_config(options, args);
+    apply_cache_config(options, args)?;
+    apply_flags(options, args);
+
+    Ok(())
 }
 
 /// リクエスト設定の適用
-fn apply_request_config(config: &mut Config, args: &Args) {
-    if let Some(hostname) = &args.hostname {
-        config.url = API_URL.replace("example.com", hostname);
+fn apply_request_config(options: &mut Options, args: &Args) -> Result<()> {
+    let hostnames = read_hostnames(args)?;
+
+    if args.input_file.is_some() || hostnames.len() > 1 {
+        options.queries = hostnames
+            .iter()
+            .map(|hostname| build_query(args, Some(hostname)).build())
+            .collect::<Result<_>>()?;
+    } else {
+        let builder = build_query(args, hostnames.first());
+        if builder.has_search() {
+            let query = builder.build()?;
+            options.url = query.to_url();
+            options.query = Some(query);
+        }
+    }
+
+    if let Some(input) = &args.input {
+        options.input = Some(input.clone());
     }
 
     if args.timeout != DEFAULT_TIMEOUT_SECS {
-        config.timeout = args.timeout;
+        options.config.timeout = args.timeout;
+    }
+
+    if args.concurrency != DEFAULT_CONCURRENCY {
+        options.config.concurrency = args.concurrency;
+    }
+
+    if args.request_delay != DEFAULT_REQUEST_DELAY {
+        options.config.request_delay = args.request_delay;
     }
+
+    Ok(())
+}
+
+/// --hostname と --input-file からホスト名の一覧を作成（空行と # で始まる行は無視）
+fn read_hostnames(args: &Args) -> Result<Vec<String>> {
+    let mut hostnames = args.hostname.clone();
+
+    let Some(path) = &args.input_file else {
+        return Ok(hostnames);
+    };
+
+    let content = if path == STDIN_PATH {
+        io::read_to_string(io::stdin())
+    } else {
+        fs::read_to_string(path)
+    }
+    .map_err(|e| {
+        Error::Config(
+            ERROR_READ_INPUT_FILE
+                .replace("{1}", path)
+                .replace("{2}", &e.to_string()),
+        )
+    })?;
+
+    hostnames.extend(
+        content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(String::from),
+    );
+
+    if hostnames.is_empty() {
+        return Err(Error::Config(ERROR_EMPTY_INPUT_FILE.replace("{}", path)));
+    }
+
+    Ok(hostnames)
+}
+
+/// 検索オプションからクエリビルダーを作成
+fn build_query(args: &Args, hostname: Option<&String>) -> QueryBuilder {
+    let searches = [
+        (SearchField::Q, hostname),
+        (SearchField::Identity, args.identity.as_ref()),
+        (SearchField::CommonName, args.cn.as_ref()),
+        (SearchField::Organization, args.org.as_ref()),
+        (SearchField::Serial, args.serial.as_ref()),
+        (SearchField::Sha1, args.sha1.as_ref()),
+        (SearchField::Sha256, args.sha256.as_ref()),
+        (SearchField::SpkiSha256, args.spkisha256.as_ref()),
+        (SearchField::CaId, args.caid.as_ref()),
+    ];
+
+    searches
+        .into_iter()
+        .filter_map(|(field, value)| value.map(|value| (field, value)))
+        .fold(Query::builder(), |builder, (field, value)| {
+            builder.search(field, value)
+        })
+        .exclude_expired(args.exclude_expired)
+        .deduplicate(args.deduplicate)
+        .match_mode(args.match_mode)
 }
 
 /// 出力設定の適用
-fn apply_output_config(config: &mut Config, args: &Args) {
-    if let Some(format) = &args.format {
-        config.format = match format.as_str() {
-            "csv" => Format::Csv,
-            "raw" => Format::Raw,
-            "table" => Format::Table,
-            _ => Format::Table,
-        };
+fn apply_output_config(options: &mut Options, args: &Args) -> Result<()> {
+    if let Some(format) = args.format {
+        options.format = format;
     }
 
-    if args.column_names.len() > 0 {
-        config.column_names = args.column_names.clone();
+    if let Some(name_value_format) = &args.name_value_format {
+        options.name_value_format = NameValueFormat::from_name(name_value_format);
+    }
+
+    if let Some(separator) = &args.name_value_separator {
+        options.name_value_separator = separator.replace("\\n", "\n").replace("\\t", "\t");
+    }
+
+    if !args.sort_keys.is_empty() {
+        options.sort_keys = args
+            .sort_keys
+            .iter()
+            .map(|key| SortKey::parse(key))
+            .collect::<Result<_>>()?;
+    }
+
+    if args.group_by.is_some() {
+        options.group_by = args.group_by;
+    }
+
+    options.limit = args.limit;
+    options.offset = args.offset;
+    options.page_size = args.page_size.map(NonZeroUsize::get);
+
+    if args.max_rows != DEFAULT_MAX_ROWS {
+        options.max_rows = args.max_rows;
+    }
+
+    if !args.column_names.is_empty() {
+        options.column_names = args.column_names.clone();
 
-        return;
+        return Ok(());
     }
 
-    config.column_names = vec![
+    options.column_names = vec![
         COLUMN_ID.to_string(),
         COLUMN_COMMON_NAME.to_string(),
         COLUMN_ENTRY_TIMESTAMP.to_string(),
@@ -138,26 +669,134 @@ fn apply_output_config(config: &mut Config, args: &Args) {
         COLUMN_RESULT_COUNT.to_string(),
         COLUMN_SERIAL_NUMBER.to_string(),
     ];
+
+    if !options.queries.is_empty() {
+        options.column_names.push(COLUMN_QUERY.to_string());
+    }
+
+    if args.dedupe {
+        options.column_names.push(COLUMN_LOG_ENTRIES.to_string());
+    }
+
+    Ok(())
+}
+
+/// 絞り込み設定の適用
+fn apply_filter_config(options: &mut Options, args: &Args) -> Result<()> {
+    if args.only_valid {
+        options.config.filters.validity = Some(Validity::Valid);
+    }
+
+    if args.only_expired {
+        options.config.filters.validity = Some(Validity::Expired);
+    }
+
+    if let Some(expiring_within) = &args.expiring_within {
+        options.config.filters.expiring_within = Some(parse_duration(expiring_within)?);
+    }
+
+    if let Some(as_of) = &args.as_of {
+        options.config.filters.as_of = Some(Timestamp::parse(as_of)?);
+    }
+
+    let time_specs = [
+        (&args.logged_after, &mut options.config.filters.logged_after),
+        (&args.logged_before, &mut options.config.filters.logged_before),
+        (&args.issued_after, &mut options.config.filters.issued_after),
+        (&args.issued_before, &mut options.config.filters.issued_before),
+    ];
+
+    for (arg, filter) in time_specs {
+        if let Some(value) = arg {
+            *filter = Some(TimeSpec::parse(value)?);
+        }
+    }
+
+    if let Some(match_name) = &args.match_name {
+        options.config.filters.match_name = Some(Pattern::regex(match_name)?);
+    }
+
+    if let Some(exclude_name) = &args.exclude_name {
+        options.config.filters.exclude_name = Some(Pattern::regex(exclude_name)?);
+    }
+
+    if let Some(issuer) = &args.issuer {
+        options.config.filters.issuer = Some(Pattern::glob(issuer)?);
+    }
+
+    if args.exclude_wildcards {
+        options.config.filters.exclude_wildcards = true;
+    }
+
+    Ok(())
 }
 
 // リトライ設定の適用
-fn apply_retry_config(config: &mut Config, args: &Args) {
+fn apply_retry_config(options: &mut Options, args: &Args) {
     if args.retry != DEFAULT_RETRY_COUNT {
-        config.retry = args.retry;
+        options.config.retry = args.retry;
     }
 
     if args.retry_delay != DEFAULT_RETRY_DELAY {
-        config.retry_delay = args.retry_delay;
+        options.config.retry_delay = args.retry_delay;
+    }
+
+    if args.retry_max_delay != DEFAULT_RETRY_MAX_DELAY {
+        options.config.retry_max_delay = args.retry_max_delay;
+    }
+
+    if args.retry_jitter {
+        options.config.retry_jitter = true;
+    }
+
+    if args.retry_budget.is_some() {
+        options.config.retry_budget = args.retry_budget;
+    }
+}
+
+// キャッシュ設定の適用
+fn apply_cache_config(options: &mut Options, args: &Args) -> Result<()> {
+    if args.no_cache {
+        options.config.cache_mode = CacheMode::Disabled;
+    } else if args.refresh {
+        options.config.cache_mode = CacheMode::Refresh;
+    } else if args.offline {
+        options.config.cache_mode = CacheMode::Offline;
     }
+
+    // ライブラリのデフォルトではキャッシュを使用しないため、CLI ではデフォルトの保存先を補う
+    if let Some(cache_dir) = &args.cache_dir {
+        options.config.cache_dir = Some(cache_dir.clone());
+    } else if options.config.cache_dir.is_none() {
+        options.config.cache_dir = default_cache_dir();
+    }
+
+    if let Some(cache_ttl) = &args.cache_ttl {
+        options.config.cache_ttl = parse_duration(cache_ttl)?;
+    }
+
+    if let Some(cache_max_size) = args.cache_max_size {
+        options.config.cache_max_size = cache_max_size;
+    }
+
+    Ok(())
 }
 
 // フラグの適用
-fn apply_flags(config: &mut Config, args: &Args) {
+fn apply_flags(options: &mut Options, args: &Args) {
     if args.timing {
-        config.timing = true;
+        options.timing = true;
     }
 
     if args.verbose {
-        config.verbose = true;
+        options.verbose = true;
+    }
+
+    if args.force {
+        options.force = true;
+    }
+
+    if args.dedupe {
+        options.config.dedupe = true;
     }
 }
\ No newline at end of file
@@ -0,0 +1,1784 @@
+use chrono::{SecondsFormat, Utc};
+use cli_table::format::Justify;
+use cli_table::{Cell, CellStruct, Style, Table, print_stdout};
+use csv::Writer;
+use rs_crtsh::{
+    CacheMode, Config, ConfigPreset, Crt, CrtShClient, Diff, Error, Group, GroupBy, Identity, Response, ResponseInfo, Result,
+    Query, RetryEvent, SortKey, SortOrder, Stats, SubdomainOptions, Timestamp, TimingInfo, collect_subdomains, dedupe_crts,
+    diff_crts, group_crts, Delivery, WatchOptions, Watcher, Webhook, parse_crts,
+};
+use serde::de::IgnoredAny;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, from_str, json};
+use std::borrow::{Borrow, BorrowMut};
+use std::cmp::Ordering;
+use crate::STDIN_PATH;
+use std::fmt;
+use std::fs;
+use std::io::{self, IsTerminal, Write};
+use std::str::FromStr;
+use std::time::Duration;
+
+// デフォルト値
+pub(crate) const DEFAULT_NAME_VALUE_SEPARATOR: &str = "\n";
+pub(crate) const DEFAULT_MAX_ROWS: usize = 1000;
+
+// ファイルサイズ計算
+const BYTES_PER_KB: f64 = 1024.0;
+
+// 集計の表示
+const SECS_PER_DAY: f64 = 86400.0;
+const HISTOGRAM_WIDTH: usize = 40;
+const HISTOGRAM_BAR: &str = "#";
+
+// 認証プレースホルダー
+const BASIC_AUTH_PLACEHOLDER: &str = "Basic <credentials>";
+
+// 表示メッセージ
+const SEARCH_MSG: &str = "* Search: {1}={2} ({3})";
+const TIMING_HEADER: &str = "--- Timing Information ---";
+const RETRY_ATTEMPT_PREFIX: &str = "--- Retry Attempt {} ---";
+const RESPONSE_RECEIVED_MSG: &str = "Response received: {}";
+const BODY_READ_TIME_MSG: &str = "Body read time: {}";
+const TOTAL_TIME_MSG: &str = "Total time: {}";
+const RESPONSE_SIZE_MSG: &str = "Response size: {1} bytes ({2} KB)";
+const THROUGHPUT_MSG: &str = "Throughput: {} KB/s";
+const HTTP_RETRY_MSG: &str = "HTTP {1} - retrying in {2}s...";
+const REQUEST_ERROR_RETRY_MSG: &str = "Request error: {1} - retrying in {2}s...";
+const INPUT_MSG: &str = "* Read {1} bytes from {2}";
+const WATCH_MSG: &str = "* {1}: {2} new certificates ({3} seen)";
+const WATCH_BASELINE_MSG: &str = "* {1}: recorded {2} certificates as seen without output ({3} seen)";
+const DIFF_ADDED_HEADER: &str = "Added certificates ({})";
+const DIFF_REMOVED_HEADER: &str = "Removed certificates ({})";
+const DIFF_NEW_IDENTITIES_HEADER: &str = "New identities ({})";
+const DIFF_REMOVED_IDENTITIES_HEADER: &str = "Removed identities ({})";
+const DIFF_NEW_ISSUERS_HEADER: &str = "New issuers ({})";
+const DIFF_SUMMARY_HEADER: &str = "{1} -> {2}";
+const DIFF_SUMMARY_CERTIFICATES_MSG: &str = "certificates: +{1} -{2}";
+const DIFF_SUMMARY_LIST_MSG: &str = "{1}: {2}";
+const DIFF_NO_CHANGES_MSG: &str = "No changes";
+const DIFF_SUMMARY_NEW_IDENTITIES: &str = "+identities";
+const DIFF_SUMMARY_REMOVED_IDENTITIES: &str = "-identities";
+const DIFF_SUMMARY_NEW_ISSUERS: &str = "+issuers";
+const WEBHOOK_DRY_RUN_MSG: &str = "* Webhook dry run ({1}): {2}";
+const WEBHOOK_SENT_MSG: &str = "* Webhook sent ({1}): {2}";
+const WEBHOOK_FAILED_MSG: &str = "Webhook failed ({1}): {2}: {3}";
+const CACHE_HIT_MSG: &str = "* Cache hit: {1} (fetched {2}s ago)";
+const DEDUPE_MSG: &str = "* Merged {1} log entries into {2} certificates";
+const GROUP_MONTH_HEADER: &str = "Month";
+const GROUP_COUNT_HEADER: &str = "Count";
+const GROUP_EARLIEST_NOT_BEFORE_HEADER: &str = "Earliest Not Before";
+const GROUP_LATEST_NOT_AFTER_HEADER: &str = "Latest Not After";
+const STATS_TOTAL_MSG: &str = "Total certificates: {}";
+const STATS_UNIQUE_SERIALS_MSG: &str = "Unique serial numbers: {}";
+const STATS_UNIQUE_IDENTITIES_MSG: &str = "Unique identities: {}";
+const STATS_WILDCARDS_MSG: &str = "Wildcard certificates: {}";
+const STATS_ISSUERS_HEADER: &str = "Certificates per issuer organization:";
+const STATS_MONTHLY_HEADER: &str = "Issuance per month (not_before):";
+const STATS_VALIDITY_MSG: &str = "Validity (days): avg {1}, min {2}, max {3}";
+const STATS_SOONEST_EXPIRY_MSG: &str = "Soonest upcoming expiry: {1} (crt.sh ID {2}, {3})";
+const BATCH_QUERY_MSG: &str = "* {1}: {2} certificates";
+const BATCH_SUMMARY_HEADER: &str = "--- Batch Summary ---";
+const BATCH_SUMMARY_MSG: &str = "Queries: {1} (succeeded: {2}, failed: {3})";
+const BATCH_FAILURE_MSG: &str = "  {1}: {2}";
+const PAGE_PROMPT: &str = "-- Page {1}/{2} -- Enter: next page, q: quit ";
+const TOO_MANY_ROWS_WARNING: &str = "Warning: {}";
+
+// エラーメッセージ
+const ERROR_READ_INPUT: &str = "Failed to read input '{1}': {2}";
+const ERROR_UNKNOWN_SORT_COLUMN: &str = "Unknown sort column: {}";
+const ERROR_UNKNOWN_FORMAT: &str = "Unknown format: {} (expected table, csv, json, jsonl, stats or raw)";
+const ERROR_TOO_MANY_ROWS: &str = "The output would contain {1} rows, exceeding --max-rows {2} (use --limit, filters or --force)";
+
+// 差分の比較対象にキャッシュを指定する接頭辞
+pub(crate) const DIFF_CACHE_PREFIX: &str = "cache:";
+
+// カラム名
+pub(crate) const COLUMN_ID: &str = "id";
+pub(crate) const COLUMN_COMMON_NAME: &str = "common_name";
+pub(crate) const COLUMN_ENTRY_TIMESTAMP: &str = "entry_timestamp";
+pub(crate) const COLUMN_ISSUER_CA_ID: &str = "issuer_ca_id";
+pub(crate) const COLUMN_ISSUER_NAME: &str = "issuer_name";
+pub(crate) const COLUMN_ISSUER_COUNTRY: &str = "issuer_country";
+pub(crate) const COLUMN_ISSUER_ORG: &str = "issuer_org";
+pub(crate) const COLUMN_ISSUER_OU: &str = "issuer_ou";
+pub(crate) const COLUMN_ISSUER_CN: &str = "issuer_cn";
+pub(crate) const COLUMN_NAME_VALUE: &str = "name_value";
+pub(crate) const COLUMN_NOT_BEFORE: &str = "not_before";
+pub(crate) const COLUMN_NOT_AFTER: &str = "not_after";
+pub(crate) const COLUMN_RESULT_COUNT: &str = "result_count";
+pub(crate) const COLUMN_SERIAL_NUMBER: &str = "serial_number";
+pub(crate) const COLUMN_LOG_ENTRIES: &str = "log_entries";
+pub(crate) const COLUMN_QUERY: &str = "query";
+
+/// 出力形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    Csv,
+    Json,
+    Jsonl,
+    Raw,
+    Stats,
+    #[default]
+    Table,
+}
+
+/// name_value カラムの出力方法
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NameValueFormat {
+    /// 区切り文字で連結
+    Joined,
+    /// 識別名ごとに 1 行
+    Explode,
+    /// JSON 配列
+    Json,
+}
+
+/// 検索対象と出力の設定（config はクライアントに渡すリクエスト・リトライ・キャッシュの設定）
+#[derive(Debug, Clone)]
+pub struct Options {
+    pub config: Config,
+    pub timing: bool,
+    pub url: String,
+    pub verbose: bool,
+    pub format: Format,
+    pub column_names: Vec<String>,
+    pub name_value_format: NameValueFormat,
+    pub name_value_separator: String,
+    pub query: Option<Query>,
+    pub sort_keys: Vec<SortKey>,
+    pub limit: Option<usize>,
+    pub offset: usize,
+    pub page_size: Option<usize>,
+    pub max_rows: usize,
+    pub force: bool,
+    pub group_by: Option<GroupBy>,
+    /// 一括検索のクエリ（空の場合は url を使用）
+    pub queries: Vec<Query>,
+    /// 取得する代わりに読み込む保存済みのレスポンスボディ（`-` は標準入力）
+    pub input: Option<String>,
+    /// watch で新しい証明書を通知する Webhook
+    pub webhooks: Vec<Webhook>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            config: Config::default(),
+            timing: false,
+            url: String::new(),
+            verbose: false,
+            format: Format::default(),
+            column_names: Vec::new(),
+            name_value_format: NameValueFormat::Joined,
+            name_value_separator: DEFAULT_NAME_VALUE_SEPARATOR.to_string(),
+            query: None,
+            sort_keys: Vec::new(),
+            limit: None,
+            offset: 0,
+            page_size: None,
+            max_rows: DEFAULT_MAX_ROWS,
+            force: false,
+            group_by: None,
+            queries: Vec::new(),
+            input: None,
+            webhooks: Vec::new(),
+        }
+    }
+}
+
+/// 設定ファイルのプリセット（出力の設定とクライアントの設定）
+#[derive(Debug, Deserialize)]
+pub struct OptionsPreset {
+    url: Option<String>,
+    timing: Option<bool>,
+    verbose: Option<bool>,
+    format: Option<Format>,
+    max_rows: Option<usize>,
+    #[serde(default)]
+    webhook: Vec<Webhook>,
+    #[serde(flatten)]
+    config: ConfigPreset,
+}
+
+impl From<OptionsPreset> for Options {
+    /// プリセットから Options を作成
+    fn from(preset: OptionsPreset) -> Self {
+        Options {
+            config: Config::from(preset.config),
+            timing: preset.timing.unwrap_or(false),
+            url: preset.url.unwrap_or_default(),
+            verbose: preset.verbose.unwrap_or(false),
+            format: preset.format.unwrap_or_default(),
+            max_rows: preset.max_rows.unwrap_or(DEFAULT_MAX_ROWS),
+            webhooks: preset.webhook,
+            ..Options::default()
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    /// 形式名から Format を作成
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "csv" => Ok(Format::Csv),
+            "json" => Ok(Format::Json),
+            "jsonl" => Ok(Format::Jsonl),
+            "raw" => Ok(Format::Raw),
+            "stats" => Ok(Format::Stats),
+            "table" => Ok(Format::Table),
+            _ => Err(ERROR_UNKNOWN_FORMAT.replace("{}", s)),
+        }
+    }
+}
+
+impl NameValueFormat {
+    /// 形式名から NameValueFormat を作成（不明な場合は Joined）
+    pub fn from_name(name: &str) -> NameValueFormat {
+        match name {
+            "explode" => NameValueFormat::Explode,
+            "json" => NameValueFormat::Json,
+            _ => NameValueFormat::Joined,
+        }
+    }
+}
+
+// 出力する 1 行分のデータ
+struct Record<'a> {
+    crt: &'a Crt,
+    name_value: String,
+    identities: Vec<Identity>,
+}
+
+// カラム定義
+struct ColumnDefinition {
+    column_name: &'static str,
+    csv_header: &'static str,
+    table_header: &'static str,
+    extract_csv_value: fn(&Record) -> String,
+    extract_table_cell: fn(&Record) -> CellStruct,
+    extract_json_value: fn(&Record) -> Value,
+    compare: fn(&Crt, &Crt) -> Ordering,
+}
+
+// 全カラムの定義
+const COLUMN_DEFINITIONS: &[ColumnDefinition] = &[
+    ColumnDefinition {
+        column_name: COLUMN_QUERY,
+        csv_header: "Query",
+        table_header: "Query",
+        extract_csv_value: |record| record.crt.query.clone().unwrap_or_default(),
+        extract_table_cell: |record| record.crt.query.clone().unwrap_or_default().cell(),
+        extract_json_value: |record| json!(record.crt.query),
+        compare: |a, b| a.query.cmp(&b.query),
+    },
+    ColumnDefinition {
+        column_name: COLUMN_ID,
+        csv_header: "id",
+        table_header: "crt.sh ID",
+        extract_csv_value: |record| record.crt.id.to_string(),
+        extract_table_cell: |record| record.crt.id.cell().justify(Justify::Right),
+        extract_json_value: |record| json!(record.crt.id),
+        compare: |a, b| a.id.cmp(&b.id),
+    },
+    ColumnDefinition {
+        column_name: COLUMN_COMMON_NAME,
+        csv_header: "Matching Identities",
+        table_header: "Matching Identities",
+        extract_csv_value: |record| record.crt.common_name.clone(),
+        extract_table_cell: |record| record.crt.common_name.clone().cell(),
+        extract_json_value: |record| json!(record.crt.common_name),
+        compare: |a, b| a.common_name.cmp(&b.common_name),
+    },
+    ColumnDefinition {
+        column_name: COLUMN_ENTRY_TIMESTAMP,
+        csv_header: "Logged At",
+        table_header: "Logged At",
+        extract_csv_value: |record| format_entry_timestamp(record.crt),
+        extract_table_cell: |record| format_entry_timestamp(record.crt).cell(),
+        extract_json_value: |record| json!(record.crt.entry_timestamp.as_ref().map(Timestamp::to_rfc3339)),
+        compare: |a, b| a.entry_timestamp.cmp(&b.entry_timestamp),
+    },
+    ColumnDefinition {
+        column_name: COLUMN_ISSUER_CA_ID,
+        csv_header: "Issuer CA ID",
+        table_header: "Issuer CA ID",
+        extract_csv_value: |record| record.crt.issuer_ca_id.to_string(),
+        extract_table_cell: |record| record.crt.issuer_ca_id.to_string().cell().justify(Justify::Right),
+        extract_json_value: |record| json!(record.crt.issuer_ca_id),
+        compare: |a, b| a.issuer_ca_id.cmp(&b.issuer_ca_id),
+    },
+    ColumnDefinition {
+        column_name: COLUMN_ISSUER_NAME,
+        csv_header: "Issuer Name",
+        table_header: "Issuer Name",
+        extract_csv_value: |record| record.crt.issuer_name.clone(),
+        extract_table_cell: |record| record.crt.issuer_name.clone().cell(),
+        extract_json_value: |record| json!(record.crt.issuer_name),
+        compare: |a, b| a.issuer_name.cmp(&b.issuer_name),
+    },
+    ColumnDefinition {
+        column_name: COLUMN_ISSUER_COUNTRY,
+        csv_header: "Issuer Country",
+        table_header: "Issuer Country",
+        extract_csv_value: |record| issuer_attribute(record, "C"),
+        extract_table_cell: |record| issuer_attribute(record, "C").cell(),
+        extract_json_value: |record| json!(record.crt.issuer().get("C")),
+        compare: |a, b| a.issuer().get("C").cmp(&b.issuer().get("C")),
+    },
+    ColumnDefinition {
+        column_name: COLUMN_ISSUER_ORG,
+        csv_header: "Issuer Organization",
+        table_header: "Issuer Organization",
+        extract_csv_value: |record| issuer_attribute(record, "O"),
+        extract_table_cell: |record| issuer_attribute(record, "O").cell(),
+        extract_json_value: |record| json!(record.crt.issuer().get("O")),
+        compare: |a, b| a.issuer().get("O").cmp(&b.issuer().get("O")),
+    },
+    ColumnDefinition {
+        column_name: COLUMN_ISSUER_OU,
+        csv_header: "Issuer Organizational Unit",
+        table_header: "Issuer Organizational Unit",
+        extract_csv_value: |record| issuer_attribute(record, "OU"),
+        extract_table_cell: |record| issuer_attribute(record, "OU").cell(),
+        extract_json_value: |record| json!(record.crt.issuer().get("OU")),
+        compare: |a, b| a.issuer().get("OU").cmp(&b.issuer().get("OU")),
+    },
+    ColumnDefinition {
+        column_name: COLUMN_ISSUER_CN,
+        csv_header: "Issuer Common Name",
+        table_header: "Issuer Common Name",
+        extract_csv_value: |record| issuer_attribute(record, "CN"),
+        extract_table_cell: |record| issuer_attribute(record, "CN").cell(),
+        extract_json_value: |record| json!(record.crt.issuer().get("CN")),
+        compare: |a, b| a.issuer().get("CN").cmp(&b.issuer().get("CN")),
+    },
+    ColumnDefinition {
+        column_name: COLUMN_NAME_VALUE,
+        csv_header: "Name Value",
+        table_header: "Name Value",
+        extract_csv_value: |record| record.name_value.clone(),
+        extract_table_cell: |record| record.name_value.clone().cell(),
+        extract_json_value: |record| json!(record.identities),
+        compare: |a, b| a.name_value.cmp(&b.name_value),
+    },
+    ColumnDefinition {
+        column_name: COLUMN_NOT_BEFORE,
+        csv_header: "Not Before",
+        table_header: "Not Before",
+        extract_csv_value: |record| record.crt.not_before.to_string(),
+        extract_table_cell: |record| record.crt.not_before.to_string().cell(),
+        extract_json_value: |record| json!(record.crt.not_before.to_rfc3339()),
+        compare: |a, b| a.not_before.cmp(&b.not_before),
+    },
+    ColumnDefinition {
+        column_name: COLUMN_NOT_AFTER,
+        csv_header: "Not After",
+        table_header: "Not After",
+        extract_csv_value: |record| record.crt.not_after.to_string(),
+        extract_table_cell: |record| record.crt.not_after.to_string().cell(),
+        extract_json_value: |record| json!(record.crt.not_after.to_rfc3339()),
+        compare: |a, b| a.not_after.cmp(&b.not_after),
+    },
+    ColumnDefinition {
+        column_name: COLUMN_RESULT_COUNT,
+        csv_header: "Count",
+        table_header: "Count",
+        extract_csv_value: |record| record.crt.result_count.to_string(),
+        extract_table_cell: |record| record.crt.result_count.to_string().cell().justify(Justify::Right),
+        extract_json_value: |record| json!(record.crt.result_count),
+        compare: |a, b| a.result_count.cmp(&b.result_count),
+    },
+    ColumnDefinition {
+        column_name: COLUMN_SERIAL_NUMBER,
+        csv_header: "Serial Number",
+        table_header: "Serial Number",
+        extract_csv_value: |record| record.crt.serial_number.clone(),
+        extract_table_cell: |record| record.crt.serial_number.clone().cell(),
+        extract_json_value: |record| json!(record.crt.serial_number),
+        compare: |a, b| a.serial_number.cmp(&b.serial_number),
+    },
+    ColumnDefinition {
+        column_name: COLUMN_LOG_ENTRIES,
+        csv_header: "Log Entries",
+        table_header: "Log Entries",
+        extract_csv_value: |record| record.crt.log_entries.to_string(),
+        extract_table_cell: |record| record.crt.log_entries.to_string().cell().justify(Justify::Right),
+        extract_json_value: |record| json!(record.crt.log_entries),
+        compare: |a, b| a.log_entries.cmp(&b.log_entries),
+    },
+];
+
+// 集計結果のカラム定義（見出しは集計の単位によって変わる）
+struct GroupColumnDefinition {
+    csv_header: fn(GroupBy) -> &'static str,
+    table_header: fn(GroupBy) -> &'static str,
+    extract_csv_value: fn(&Group) -> String,
+    extract_table_cell: fn(&Group) -> CellStruct,
+}
+
+// 集計結果の全カラムの定義
+const GROUP_COLUMN_DEFINITIONS: &[GroupColumnDefinition] = &[
+    GroupColumnDefinition {
+        csv_header: |group_by| find_column(group_by.name()).map_or(GROUP_MONTH_HEADER, |col| col.csv_header),
+        table_header: |group_by| find_column(group_by.name()).map_or(GROUP_MONTH_HEADER, |col| col.table_header),
+        extract_csv_value: |group| group.key.clone(),
+        extract_table_cell: |group| group.key.clone().cell(),
+    },
+    GroupColumnDefinition {
+        csv_header: |_| GROUP_COUNT_HEADER,
+        table_header: |_| GROUP_COUNT_HEADER,
+        extract_csv_value: |group| group.count.to_string(),
+        extract_table_cell: |group| group.count.cell().justify(Justify::Right),
+    },
+    GroupColumnDefinition {
+        csv_header: |_| GROUP_EARLIEST_NOT_BEFORE_HEADER,
+        table_header: |_| GROUP_EARLIEST_NOT_BEFORE_HEADER,
+        extract_csv_value: |group| group.earliest_not_before.to_string(),
+        extract_table_cell: |group| group.earliest_not_before.to_string().cell(),
+    },
+    GroupColumnDefinition {
+        csv_header: |_| GROUP_LATEST_NOT_AFTER_HEADER,
+        table_header: |_| GROUP_LATEST_NOT_AFTER_HEADER,
+        extract_csv_value: |group| group.latest_not_after.to_string(),
+        extract_table_cell: |group| group.latest_not_after.to_string().cell(),
+    },
+];
+
+/// HTTPリクエストを実行
+pub fn execute_request(options: Options) -> Result<()> {
+    let fetched = fetch(&options)?;
+
+    let processed_response = format_response_body(&fetched.body, &options)?;
+    output_response(&processed_response, &options)?;
+
+    fetched.finish()
+}
+
+/// サブドメインを列挙して 1 行に 1 つずつ出力
+pub fn execute_subdomains(options: Options, subdomain_options: &SubdomainOptions) -> Result<()> {
+    let fetched = fetch(&options)?;
+    let crts = options.config.filters.apply(parse_crts(&fetched.body)?);
+
+    let mut stdout = io::stdout().lock();
+    for name in collect_subdomains(&crts, subdomain_options) {
+        writeln!(stdout, "{}", name).map_err(Error::output)?;
+    }
+
+    fetched.finish()
+}
+
+/// 一定間隔で検索を繰り返し、前回までに確認していない証明書のみを出力
+pub fn execute_watch(mut options: Options, watch_options: WatchOptions) -> Result<()> {
+    // 毎回 crt.sh から最新の結果を取得する（取得結果はキャッシュに保存）
+    if options.config.cache_mode == CacheMode::Use {
+        options.config.cache_mode = CacheMode::Refresh;
+    }
+
+    let watcher = Watcher::new(create_client(&options)?, watch_options);
+
+    watcher.run(
+        |watcher| watch_once(&options, watcher),
+        |e| eprintln!("Error: {}", e),
+    )
+}
+
+// 監視対象の証明書（出力用に元の JSON を保持）
+struct WatchEntry {
+    crt: Crt,
+    value: Value,
+}
+
+impl Borrow<Crt> for WatchEntry {
+    fn borrow(&self) -> &Crt {
+        &self.crt
+    }
+}
+
+/// 1 回検索し、新しい証明書を出力・通知して確認済みとして記録
+fn watch_once(options: &Options, watcher: &Watcher) -> Result<()> {
+    let fetched = fetch(options)?;
+
+    let values: Vec<Value> = from_str(&fetched.body).map_err(|e| Error::json(&fetched.body, e))?;
+    let (crts, values) = if needs_selection(options) {
+        let selected = select_values(&fetched.body, values, options)?;
+        let body = serde_json::to_string(&selected).map_err(Error::output)?;
+        (parse_crts(&body)?, selected)
+    } else {
+        (parse_crts(&fetched.body)?, values)
+    };
+
+    let entries: Vec<WatchEntry> = crts
+        .into_iter()
+        .zip(values)
+        .map(|(crt, value)| WatchEntry { crt, value })
+        .collect();
+
+    let report = watcher.check(entries, |new| {
+        let values: Vec<&Value> = new.iter().map(|entry| &entry.value).collect();
+        let body = serde_json::to_string(&values).map_err(Error::output)?;
+        output_response(&body, options)
+    })?;
+
+    let delivery_result = display_deliveries(report.deliveries, options);
+
+    let message = if watcher.options().baseline {
+        WATCH_BASELINE_MSG
+    } else {
+        WATCH_MSG
+    };
+    eprintln!(
+        "{}",
+        message
+            .replace("{1}", &Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true))
+            .replace("{2}", &report.new.len().to_string())
+            .replace("{3}", &report.seen.to_string())
+    );
+
+    delivery_result?;
+    fetched.finish()
+}
+
+/// Webhook の送信結果を表示（失敗した送信がある場合は最初のエラーを返す）
+fn display_deliveries(deliveries: Vec<(Webhook, Delivery)>, options: &Options) -> Result<()> {
+    let mut result = Ok(());
+
+    for (webhook, delivery) in deliveries {
+        match delivery {
+            Delivery::Sent => {
+                if options.verbose {
+                    eprintln!("{}", webhook_message(WEBHOOK_SENT_MSG, &webhook));
+                }
+            }
+            Delivery::DryRun(payload) => {
+                eprintln!("{}", webhook_message(WEBHOOK_DRY_RUN_MSG, &webhook));
+                eprintln!("{}", payload);
+            }
+            Delivery::Failed(e) => {
+                eprintln!(
+                    "{}",
+                    webhook_message(WEBHOOK_FAILED_MSG, &webhook).replace("{3}", &e.to_string())
+                );
+                if result.is_ok() {
+                    result = Err(e);
+                }
+            }
+        }
+    }
+
+    result
+}
+
+/// Webhook の種類と URL（トークンを含むパスは伏せる）を埋め込んだメッセージ
+fn webhook_message(template: &str, webhook: &Webhook) -> String {
+    template
+        .replace("{1}", webhook.kind.name())
+        .replace("{2}", &webhook.redacted_url())
+}
+
+/// 差分の比較対象
+#[derive(Debug, Clone)]
+pub enum DiffSource {
+    /// 保存済みの crt.sh の JSON（`-` は標準入力）
+    File(String),
+    /// キャッシュ済みの URL のレスポンス
+    Cache(String),
+}
+
+/// 差分のオプション
+#[derive(Debug, Clone)]
+pub struct DiffOptions {
+    pub old: DiffSource,
+    pub new: DiffSource,
+    /// 件数と変化した名前のみの簡潔な要約を出力
+    pub summary: bool,
+}
+
+impl fmt::Display for DiffSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiffSource::File(path) => f.write_str(path),
+            DiffSource::Cache(url) => write!(f, "{}{}", DIFF_CACHE_PREFIX, url),
+        }
+    }
+}
+
+/// 2 つの保存済みの検索結果を比較して差分を出力
+pub fn execute_diff(options: Options, diff_options: &DiffOptions) -> Result<()> {
+    let old = load_diff_source(&diff_options.old, &options)?;
+    let new = load_diff_source(&diff_options.new, &options)?;
+    let mut diff = diff_crts(&old, &new);
+
+    let sort_columns = get_sort_columns(&options)?;
+    diff.added.sort_by(|a, b| compare_crts(a, b, &sort_columns));
+    diff.removed.sort_by(|a, b| compare_crts(a, b, &sort_columns));
+
+    if diff_options.summary {
+        return print_diff_summary(&diff, diff_options).map_err(Error::output);
+    }
+
+    match options.format {
+        Format::Json => output_diff_json(&diff, &options),
+        _ => output_diff_table(&diff, &options),
+    }
+}
+
+/// 比較対象を読み込み、重複統合・絞り込み条件を適用
+fn load_diff_source(source: &DiffSource, options: &Options) -> Result<Vec<Crt>> {
+    let body = match source {
+        DiffSource::File(path) => read_input(path, options)?,
+        DiffSource::Cache(url) => {
+            // 有効期限切れのエントリも含めてキャッシュのみから読み込む
+            let mut cache_config = options.config.clone();
+            cache_config.cache_mode = CacheMode::Offline;
+            CrtShClient::new(cache_config)?.fetch(url)?.body
+        }
+    };
+
+    let mut crts = parse_crts(&body)?;
+    if options.config.dedupe {
+        crts = dedupe_crts(crts);
+    }
+
+    Ok(options.config.filters.apply(crts))
+}
+
+/// 差分をセクションごとのテーブルと一覧で出力
+fn output_diff_table(diff: &Diff, options: &Options) -> Result<()> {
+    check_row_limit(diff.added.len() + diff.removed.len(), options)?;
+
+    println!("{}", DIFF_ADDED_HEADER.replace("{}", &diff.added.len().to_string()));
+    print_crt_table(&diff.added, options)?;
+
+    println!();
+    println!("{}", DIFF_REMOVED_HEADER.replace("{}", &diff.removed.len().to_string()));
+    print_crt_table(&diff.removed, options)?;
+
+    let lists = [
+        (DIFF_NEW_IDENTITIES_HEADER, identity_values(&diff.new_identities)),
+        (DIFF_REMOVED_IDENTITIES_HEADER, identity_values(&diff.removed_identities)),
+        (DIFF_NEW_ISSUERS_HEADER, diff.new_issuers.iter().map(String::as_str).collect()),
+    ];
+
+    let mut stdout = io::stdout().lock();
+    for (header, values) in lists {
+        writeln!(stdout).map_err(Error::output)?;
+        writeln!(stdout, "{}", header.replace("{}", &values.len().to_string()))
+            .map_err(Error::output)?;
+        for value in values {
+            writeln!(stdout, "  {}", value).map_err(Error::output)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// 証明書をテーブルで出力（証明書がない場合は何も出力しない）
+fn print_crt_table(crts: &[Crt], options: &Options) -> Result<()> {
+    if crts.is_empty() {
+        return Ok(());
+    }
+
+    let active_columns = get_active_columns(options);
+    let table: Vec<Vec<CellStruct>> = build_records(crts, options)
+        .iter()
+        .map(|record| build_table_row(record, &active_columns))
+        .collect();
+
+    print_stdout(table.table().title(build_table_header(&active_columns))).map_err(Error::output)
+}
+
+/// 差分を JSON で出力（証明書は選択したカラムのオブジェクト）
+fn output_diff_json(diff: &Diff, options: &Options) -> Result<()> {
+    let active_columns = get_active_columns(options);
+    let objects = |crts: &[Crt]| -> Vec<Value> {
+        build_records(crts, options)
+            .iter()
+            .map(|record| build_json_object(record, &active_columns))
+            .collect()
+    };
+
+    let result = json!({
+        "added": objects(&diff.added),
+        "removed": objects(&diff.removed),
+        "new_identities": identity_values(&diff.new_identities),
+        "removed_identities": identity_values(&diff.removed_identities),
+        "new_issuers": diff.new_issuers,
+    });
+
+    let mut stdout = io::stdout().lock();
+    serde_json::to_writer_pretty(&mut stdout, &result).map_err(Error::output)?;
+    writeln!(stdout).map_err(Error::output)
+}
+
+/// 差分の件数と変化した名前を簡潔に出力
+fn print_diff_summary(diff: &Diff, diff_options: &DiffOptions) -> io::Result<()> {
+    let mut stdout = io::stdout().lock();
+
+    writeln!(
+        stdout,
+        "{}",
+        DIFF_SUMMARY_HEADER
+            .replace("{1}", &diff_options.old.to_string())
+            .replace("{2}", &diff_options.new.to_string())
+    )?;
+
+    if diff.is_empty() {
+        return writeln!(stdout, "{}", DIFF_NO_CHANGES_MSG);
+    }
+
+    writeln!(
+        stdout,
+        "{}",
+        DIFF_SUMMARY_CERTIFICATES_MSG
+            .replace("{1}", &diff.added.len().to_string())
+            .replace("{2}", &diff.removed.len().to_string())
+    )?;
+
+    let lists = [
+        (DIFF_SUMMARY_NEW_IDENTITIES, identity_values(&diff.new_identities)),
+        (DIFF_SUMMARY_REMOVED_IDENTITIES, identity_values(&diff.removed_identities)),
+        (DIFF_SUMMARY_NEW_ISSUERS, diff.new_issuers.iter().map(String::as_str).collect()),
+    ];
+
+    for (label, values) in lists {
+        if !values.is_empty() {
+            writeln!(
+                stdout,
+                "{}",
+                DIFF_SUMMARY_LIST_MSG
+                    .replace("{1}", label)
+                    .replace("{2}", &values.join(", "))
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// 識別名の文字列の一覧
+fn identity_values(identities: &[Identity]) -> Vec<&str> {
+    identities.iter().map(|identity| identity.value.as_str()).collect()
+}
+
+// 取得したレスポンスボディ（一括検索の場合は全クエリの結果を連結したもの）
+struct Fetched {
+    body: String,
+    batch: Option<BatchSummary>,
+}
+
+// 一括検索の結果
+struct BatchSummary {
+    total: usize,
+    failures: Vec<(String, Error)>,
+}
+
+impl Fetched {
+    /// 一括検索の場合は結果の概要を表示し、失敗したクエリがあればエラーを返す
+    fn finish(self) -> Result<()> {
+        let Some(batch) = self.batch else {
+            return Ok(());
+        };
+
+        eprintln!("{}", BATCH_SUMMARY_HEADER);
+        eprintln!(
+            "{}",
+            BATCH_SUMMARY_MSG
+                .replace("{1}", &batch.total.to_string())
+                .replace("{2}", &(batch.total - batch.failures.len()).to_string())
+                .replace("{3}", &batch.failures.len().to_string())
+        );
+        for (query, error) in &batch.failures {
+            eprintln!(
+                "{}",
+                BATCH_FAILURE_MSG
+                    .replace("{1}", query)
+                    .replace("{2}", &error.to_string())
+            );
+        }
+
+        if batch.failures.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::BatchFailed {
+                failed: batch.failures.len(),
+                total: batch.total,
+            })
+        }
+    }
+}
+
+/// 単一の URL、または一括検索の全クエリを取得（--input 指定時はファイルから読み込む）
+fn fetch(options: &Options) -> Result<Fetched> {
+    if let Some(path) = &options.input {
+        return Ok(Fetched {
+            body: read_input(path, options)?,
+            batch: None,
+        });
+    }
+
+    if options.queries.is_empty() {
+        let response = fetch_response(options)?;
+        return Ok(Fetched {
+            body: response.body,
+            batch: None,
+        });
+    }
+
+    fetch_batch(options)
+}
+
+/// 保存済みのレスポンスボディを読み込む（`-` は標準入力）
+fn read_input(path: &str, options: &Options) -> Result<String> {
+    let body = if path == STDIN_PATH {
+        io::read_to_string(io::stdin())
+    } else {
+        fs::read_to_string(path)
+    }
+    .map_err(|e| {
+        Error::Config(
+            ERROR_READ_INPUT
+                .replace("{1}", path)
+                .replace("{2}", &e.to_string()),
+        )
+    })?;
+
+    if options.verbose {
+        println!(
+            "{}",
+            INPUT_MSG
+                .replace("{1}", &body.len().to_string())
+                .replace("{2}", path)
+        );
+        println!();
+    }
+
+    Ok(body)
+}
+
+/// リクエストを送信してレスポンスを取得（詳細・タイミング情報を表示）
+fn fetch_response(options: &Options) -> Result<Response> {
+    let client = create_client(options)?;
+
+    display_search_info(options);
+
+    let result = client.fetch(&options.url);
+
+    // キャッシュから返した場合はリクエストを送信していないため、リクエスト情報は表示しない
+    match &result {
+        Ok(Response {
+            cache_age: Some(age),
+            ..
+        }) => display_cache_hit(*age, options),
+        Err(Error::Cache(_)) => {}
+        _ => display_request_info(&client, options),
+    }
+
+    let response = result?;
+    if response.cache_age.is_none() {
+        display_response_info(&response.info, options);
+        display_timing_info(&response.timing, response.body.len(), options);
+    }
+
+    Ok(response)
+}
+
+/// 一括検索を実行し、各行にクエリを付与して連結（失敗したクエリは記録して続行）
+fn fetch_batch(options: &Options) -> Result<Fetched> {
+    let client = create_client(options)?;
+    let urls: Vec<String> = options.queries.iter().map(Query::to_url).collect();
+    let responses = client.fetch_all(&urls);
+
+    let mut rows = Vec::new();
+    let mut failures = Vec::new();
+
+    for ((query, url), response) in options.queries.iter().zip(&urls).zip(responses) {
+        let label = query.term().as_str();
+
+        match response.and_then(|response| tag_rows(&response.body, label)) {
+            Ok(tagged) => {
+                if options.verbose {
+                    println!(
+                        "{}",
+                        BATCH_QUERY_MSG
+                            .replace("{1}", url)
+                            .replace("{2}", &tagged.len().to_string())
+                    );
+                }
+                rows.extend(tagged);
+            }
+            Err(error) => failures.push((label.to_string(), error)),
+        }
+    }
+
+    if options.verbose {
+        println!();
+    }
+
+    Ok(Fetched {
+        body: serde_json::to_string(&rows).map_err(Error::output)?,
+        batch: Some(BatchSummary {
+            total: options.queries.len(),
+            failures,
+        }),
+    })
+}
+
+/// レスポンスボディの各行にクエリを付与
+fn tag_rows(body: &str, query: &str) -> Result<Vec<Value>> {
+    let mut rows: Vec<Value> = from_str(body).map_err(|e| Error::json(body, e))?;
+
+    for row in &mut rows {
+        if let Value::Object(object) = row {
+            object.insert(COLUMN_QUERY.to_string(), json!(query));
+        }
+    }
+
+    Ok(rows)
+}
+
+/// リトライ時に詳細情報を表示するクライアントを作成
+fn create_client(options: &Options) -> Result<CrtShClient> {
+    let verbose = options.verbose;
+
+    Ok(CrtShClient::new(options.config.clone())?.on_retry(move |event| {
+        if verbose {
+            display_retry_event(event);
+        }
+    }))
+}
+
+/// 検索語を表示（国際化ドメイン名を変換した場合のみ）
+fn display_search_info(options: &Options) {
+    if !options.verbose {
+        return;
+    }
+
+    if let Some(query) = &options.query {
+        let term = query.term();
+        if term.to_unicode() != term.as_str() {
+            println!(
+                "{}",
+                SEARCH_MSG
+                    .replace("{1}", query.field().param())
+                    .replace("{2}", &term.to_unicode())
+                    .replace("{3}", term.as_str())
+            );
+        }
+    }
+}
+
+/// リクエスト情報を表示
+fn display_request_info(client: &CrtShClient, options: &Options) {
+    if !options.verbose {
+        return;
+    }
+
+    println!("> GET {}", options.url);
+
+    for (name, value) in client.default_headers() {
+        let display_value = if name == reqwest::header::AUTHORIZATION {
+            BASIC_AUTH_PLACEHOLDER
+        } else {
+            value.to_str().unwrap_or("<binary>")
+        };
+        println!("> {}: {}", name, display_value);
+    }
+
+    println!();
+}
+
+/// リトライ情報を表示
+fn display_retry_event(event: &RetryEvent) {
+    let attempt = match event {
+        RetryEvent::Status {
+            attempt,
+            status,
+            delay,
+        } => {
+            println!(
+                "{}",
+                HTTP_RETRY_MSG
+                    .replace("{1}", &status.as_u16().to_string())
+                    .replace("{2}", &format!("{:.2}", delay.as_secs_f64()))
+            );
+            attempt
+        }
+        RetryEvent::Error {
+            attempt,
+            error,
+            delay,
+        } => {
+            println!(
+                "{}",
+                REQUEST_ERROR_RETRY_MSG
+                    .replace("{1}", &error.to_string())
+                    .replace("{2}", &format!("{:.2}", delay.as_secs_f64()))
+            );
+            attempt
+        }
+    };
+
+    println!(
+        "{}",
+        RETRY_ATTEMPT_PREFIX.replace("{}", &attempt.to_string())
+    );
+}
+
+/// レスポンス情報を表示
+fn display_response_info(response_info: &ResponseInfo, options: &Options) {
+    if !options.verbose {
+        return;
+    }
+
+    println!(
+        "< {:?} {} {}",
+        response_info.version(),
+        response_info.status().as_u16(),
+        response_info.status().canonical_reason().unwrap_or("")
+    );
+
+    for (name, value) in response_info.headers() {
+        println!("< {}: {}", name, value.to_str().unwrap_or("<binary>"));
+    }
+
+    println!();
+}
+
+/// キャッシュから取得したことを表示
+fn display_cache_hit(age: Duration, options: &Options) {
+    if !options.verbose {
+        return;
+    }
+
+    println!(
+        "{}",
+        CACHE_HIT_MSG
+            .replace("{1}", &options.url)
+            .replace("{2}", &age.as_secs().to_string())
+    );
+    println!();
+}
+
+/// タイミング情報を表示
+fn display_timing_info(timing_info: &TimingInfo, response_size: usize, options: &Options) {
+    if !options.timing {
+        return;
+    }
+
+    println!("{}", TIMING_HEADER);
+    println!(
+        "{}",
+        RESPONSE_RECEIVED_MSG.replace("{}", &format!("{:?}", timing_info.response_time()))
+    );
+    println!(
+        "{}",
+        BODY_READ_TIME_MSG.replace("{}", &format!("{:?}", timing_info.body_read_time()))
+    );
+    println!(
+        "{}",
+        TOTAL_TIME_MSG.replace("{}", &format!("{:?}", timing_info.total_time()))
+    );
+    println!(
+        "{}",
+        RESPONSE_SIZE_MSG
+            .replace("{1}", &response_size.to_string())
+            .replace(
+                "{2}",
+                &format!("{:.2}", response_size as f64 / BYTES_PER_KB)
+            )
+    );
+
+    if response_size > 0 && timing_info.total_time().as_secs_f64() > 0.0 {
+        let throughput =
+            response_size as f64 / timing_info.total_time().as_secs_f64() / BYTES_PER_KB;
+        println!(
+            "{}",
+            THROUGHPUT_MSG.replace("{}", &format!("{:.2}", throughput))
+        );
+    }
+
+    println!();
+}
+
+/// レスポンスボディをフォーマット（絞り込み・並べ替え・件数制限を適用）
+fn format_response_body(body: &str, options: &Options) -> Result<String> {
+    let json_value = match from_str::<Value>(body) {
+        Ok(value) => value,
+        Err(_) => return Ok(body.to_string()),
+    };
+
+    let result = match json_value {
+        Value::Array(values) if needs_selection(options) => Value::Array(select_values(body, values, options)?),
+        value => value,
+    };
+
+    let formatted = serde_json::to_string(&result).map_err(Error::output)?;
+
+    Ok(formatted)
+}
+
+/// 証明書として解析し、重複統合・絞り込み・並べ替え・件数制限を適用して返す（元の JSON を保持）
+///
+/// `values` は `body` を解析した配列。デコードエラーの位置を元のボディで示すため、証明書は `body` から解析する。
+fn select_values(body: &str, values: Vec<Value>, options: &Options) -> Result<Vec<Value>> {
+    let now = options.config.filters.reference_time();
+    let sort_columns = get_sort_columns(options)?;
+
+    let mut crts: Vec<IndexedCrt> = parse_crts(body)?
+        .into_iter()
+        .enumerate()
+        .map(|(index, crt)| IndexedCrt { index, crt })
+        .collect();
+    let mut originals: Vec<Option<Value>> = values.into_iter().map(Some).collect();
+
+    if options.config.dedupe {
+        let log_entry_count = crts.len();
+        crts = dedupe_crts(crts);
+        if options.verbose {
+            eprintln!(
+                "{}",
+                DEDUPE_MSG
+                    .replace("{1}", &log_entry_count.to_string())
+                    .replace("{2}", &crts.len().to_string())
+            );
+        }
+    }
+
+    crts.retain(|indexed| options.config.filters.matches_at(&indexed.crt, now));
+    crts.sort_by(|a, b| compare_crts(&a.crt, &b.crt, &sort_columns));
+
+    Ok(crts
+        .into_iter()
+        .skip(options.offset)
+        .take(options.limit.unwrap_or(usize::MAX))
+        .filter_map(|IndexedCrt { index, crt }| {
+            let mut value = originals.get_mut(index)?.take()?;
+            if options.config.dedupe
+                && let Value::Object(object) = &mut value
+            {
+                object.insert(COLUMN_LOG_ENTRIES.to_string(), json!(crt.log_entries));
+            }
+            Some(value)
+        })
+        .collect())
+}
+
+/// 元の JSON 配列での位置を添えた証明書（重複統合・並べ替えの後に元の値を取り出すため）
+struct IndexedCrt {
+    index: usize,
+    crt: Crt,
+}
+
+impl Borrow<Crt> for IndexedCrt {
+    fn borrow(&self) -> &Crt {
+        &self.crt
+    }
+}
+
+impl BorrowMut<Crt> for IndexedCrt {
+    fn borrow_mut(&mut self) -> &mut Crt {
+        &mut self.crt
+    }
+}
+
+/// 絞り込み・並べ替え・件数制限のいずれかが指定されているかどうか
+fn needs_selection(options: &Options) -> bool {
+    !options.config.filters.is_empty()
+        || !options.sort_keys.is_empty()
+        || options.offset > 0
+        || options.limit.is_some()
+        || options.config.dedupe
+}
+
+/// 並べ替えに使用するカラムと並び順を取得
+fn get_sort_columns(options: &Options) -> Result<Vec<(&'static ColumnDefinition, SortOrder)>> {
+    options
+        .sort_keys
+        .iter()
+        .map(|key| {
+            find_column(&key.column)
+                .map(|col| (col, key.order))
+                .ok_or_else(|| Error::Config(ERROR_UNKNOWN_SORT_COLUMN.replace("{}", &key.column)))
+        })
+        .collect()
+}
+
+/// 並べ替えのキーを順に比較
+fn compare_crts(a: &Crt, b: &Crt, sort_columns: &[(&ColumnDefinition, SortOrder)]) -> Ordering {
+    sort_columns
+        .iter()
+        .map(|(col, order)| match order {
+            SortOrder::Asc => (col.compare)(a, b),
+            SortOrder::Desc => (col.compare)(b, a),
+        })
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+/// カラム名からカラム定義を取得
+fn find_column(column_name: &str) -> Option<&'static ColumnDefinition> {
+    COLUMN_DEFINITIONS
+        .iter()
+        .find(|col| col.column_name == column_name)
+}
+
+/// 設定に基づいて有効なカラムをフィルタリング
+fn get_active_columns(options: &Options) -> Vec<&'static ColumnDefinition> {
+    COLUMN_DEFINITIONS
+        .iter()
+        .filter(|col| options.column_names.contains(&col.column_name.to_string()))
+        .collect()
+}
+
+/// CSVヘッダーを構築
+fn build_csv_header<'a>(active_columns: &'a [&'a ColumnDefinition]) -> Vec<&'a str> {
+    active_columns.iter().map(|col| col.csv_header).collect()
+}
+
+/// CSVデータ行を構築
+fn build_csv_row(record: &Record, active_columns: &[&ColumnDefinition]) -> Vec<String> {
+    active_columns
+        .iter()
+        .map(|col| (col.extract_csv_value)(record))
+        .collect()
+}
+
+/// Tableヘッダーを構築
+fn build_table_header(active_columns: &[&ColumnDefinition]) -> Vec<CellStruct> {
+    active_columns
+        .iter()
+        .map(|col| header_cell(col.table_header))
+        .collect()
+}
+
+/// Tableヘッダーのセル
+fn header_cell(name: &str) -> CellStruct {
+    name.cell().bold(true).justify(Justify::Center)
+}
+
+/// Tableデータ行を構築
+fn build_table_row(record: &Record, active_columns: &[&ColumnDefinition]) -> Vec<CellStruct> {
+    active_columns
+        .iter()
+        .map(|col| (col.extract_table_cell)(record))
+        .collect()
+}
+
+/// 発行者の識別名から属性の値を取得（存在しない場合は空文字）
+fn issuer_attribute(record: &Record, attribute: &str) -> String {
+    record
+        .crt
+        .issuer()
+        .get(attribute)
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Logged At の表示値（null は空文字）
+fn format_entry_timestamp(crt: &Crt) -> String {
+    crt.entry_timestamp
+        .as_ref()
+        .map(ToString::to_string)
+        .unwrap_or_default()
+}
+
+/// 証明書を出力行に変換（name_value の出力方法に応じて展開）
+fn build_records<'a>(crts: &'a [Crt], options: &Options) -> Vec<Record<'a>> {
+    let mut records = Vec::new();
+
+    for crt in crts {
+        let identities = crt.identities();
+        let values: Vec<&str> = identities
+            .iter()
+            .map(|identity| identity.value.as_str())
+            .collect();
+
+        match options.name_value_format {
+            NameValueFormat::Joined => records.push(Record {
+                crt,
+                name_value: values.join(&options.name_value_separator),
+                identities,
+            }),
+            NameValueFormat::Json => records.push(Record {
+                crt,
+                name_value: serde_json::to_string(&values).unwrap_or_default(),
+                identities,
+            }),
+            NameValueFormat::Explode if identities.is_empty() => records.push(Record {
+                crt,
+                name_value: String::new(),
+                identities,
+            }),
+            NameValueFormat::Explode => {
+                records.extend(identities.into_iter().map(|identity| Record {
+                    crt,
+                    name_value: identity.value.clone(),
+                    identities: vec![identity],
+                }))
+            }
+        }
+    }
+
+    records
+}
+
+/// 出力の行数（ページ分割時も全ページの合計）が上限を超える場合は中止（--force 指定時は警告のみ）
+fn check_row_limit(row_count: usize, options: &Options) -> Result<()> {
+    if options.max_rows == 0 || row_count <= options.max_rows {
+        return Ok(());
+    }
+
+    let message = ERROR_TOO_MANY_ROWS
+        .replace("{1}", &row_count.to_string())
+        .replace("{2}", &options.max_rows.to_string());
+
+    if !options.force {
+        return Err(Error::Output(message));
+    }
+
+    eprintln!("{}", TOO_MANY_ROWS_WARNING.replace("{}", &message));
+
+    Ok(())
+}
+
+/// 次のページを表示するか確認（端末以外の入力では確認せずに続行）
+fn prompt_next_page(page: usize, page_count: usize) -> Result<bool> {
+    let stdin = io::stdin();
+    if !stdin.is_terminal() {
+        return Ok(true);
+    }
+
+    eprint!(
+        "{}",
+        PAGE_PROMPT
+            .replace("{1}", &page.to_string())
+            .replace("{2}", &page_count.to_string())
+    );
+
+    let mut answer = String::new();
+    stdin.read_line(&mut answer).map_err(Error::output)?;
+
+    Ok(!answer.trim().eq_ignore_ascii_case("q"))
+}
+
+/// JSON オブジェクトを構築（キーはカラム名）
+fn build_json_object(record: &Record, active_columns: &[&ColumnDefinition]) -> Value {
+    let object: Map<String, Value> = active_columns
+        .iter()
+        .map(|col| (col.column_name.to_string(), (col.extract_json_value)(record)))
+        .collect();
+
+    Value::Object(object)
+}
+
+/// 集計キーごとの行を出力（table または csv）
+fn output_groups(groups: &[Group], group_by: GroupBy, options: &Options) -> Result<()> {
+    if matches!(options.format, Format::Csv) {
+        let header: Vec<&str> = GROUP_COLUMN_DEFINITIONS
+            .iter()
+            .map(|col| (col.csv_header)(group_by))
+            .collect();
+        let rows: Vec<Vec<String>> = groups
+            .iter()
+            .map(|group| {
+                GROUP_COLUMN_DEFINITIONS
+                    .iter()
+                    .map(|col| (col.extract_csv_value)(group))
+                    .collect()
+            })
+            .collect();
+
+        return write_csv(&header, rows, options);
+    }
+
+    let table: Vec<Vec<CellStruct>> = groups
+        .iter()
+        .map(|group| {
+            GROUP_COLUMN_DEFINITIONS
+                .iter()
+                .map(|col| (col.extract_table_cell)(group))
+                .collect()
+        })
+        .collect();
+
+    let header = || {
+        GROUP_COLUMN_DEFINITIONS
+            .iter()
+            .map(|col| header_cell((col.table_header)(group_by)))
+            .collect()
+    };
+
+    print_table(header, table, options)
+}
+
+/// 見出しの行と行の一覧を CSV で出力（行数の上限を確認してから出力する）
+fn write_csv(header: &[&str], rows: Vec<Vec<String>>, options: &Options) -> Result<()> {
+    check_row_limit(rows.len(), options)?;
+
+    let mut wtr = Writer::from_writer(io::stdout());
+    wtr.write_record(header).map_err(Error::output)?;
+
+    for row in rows {
+        wtr.write_record(row).map_err(Error::output)?;
+    }
+
+    wtr.flush().map_err(Error::output)
+}
+
+/// 行の一覧をテーブルで出力（行数の上限を確認し、--page-size 指定時はページごとに見出しを付けて出力する）
+fn print_table(
+    header: impl Fn() -> Vec<CellStruct>,
+    table: Vec<Vec<CellStruct>>,
+    options: &Options,
+) -> Result<()> {
+    check_row_limit(table.len(), options)?;
+
+    // 端末以外への出力（パイプ・リダイレクト）ではページに分割しない
+    let page_size = options
+        .page_size
+        .filter(|_| io::stdout().is_terminal())
+        .unwrap_or(table.len())
+        .max(1);
+    let page_count = table.len().div_ceil(page_size).max(1);
+    let mut rows = table.into_iter().peekable();
+
+    for page in 1..=page_count {
+        let page_rows: Vec<Vec<CellStruct>> = rows.by_ref().take(page_size).collect();
+        print_stdout(page_rows.table().title(header())).map_err(Error::output)?;
+
+        if rows.peek().is_some() && !prompt_next_page(page, page_count)? {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// 集計結果を出力
+fn print_stats(stats: &Stats) -> io::Result<()> {
+    let mut stdout = io::stdout().lock();
+
+    writeln!(stdout, "{}", STATS_TOTAL_MSG.replace("{}", &stats.total.to_string()))?;
+    writeln!(stdout, "{}", STATS_UNIQUE_SERIALS_MSG.replace("{}", &stats.unique_serials.to_string()))?;
+    writeln!(stdout, "{}", STATS_UNIQUE_IDENTITIES_MSG.replace("{}", &stats.unique_identities.to_string()))?;
+    writeln!(stdout, "{}", STATS_WILDCARDS_MSG.replace("{}", &stats.wildcard_certificates.to_string()))?;
+
+    writeln!(stdout)?;
+    writeln!(stdout, "{}", STATS_ISSUERS_HEADER)?;
+    write_counts(&mut stdout, &stats.issuer_organizations, false)?;
+
+    writeln!(stdout)?;
+    writeln!(stdout, "{}", STATS_MONTHLY_HEADER)?;
+    write_counts(&mut stdout, &stats.monthly_issuance, true)?;
+
+    if let Some(validity) = &stats.validity {
+        writeln!(stdout)?;
+        writeln!(
+            stdout,
+            "{}",
+            STATS_VALIDITY_MSG
+                .replace("{1}", &format_days(validity.average))
+                .replace("{2}", &format_days(validity.min))
+                .replace("{3}", &format_days(validity.max))
+        )?;
+    }
+
+    if let Some(expiry) = &stats.soonest_expiry {
+        writeln!(
+            stdout,
+            "{}",
+            STATS_SOONEST_EXPIRY_MSG
+                .replace("{1}", &expiry.not_after.to_string())
+                .replace("{2}", &expiry.id.to_string())
+                .replace("{3}", &expiry.common_name)
+        )?;
+    }
+
+    Ok(())
+}
+
+/// 名前と件数の一覧を出力（histogram が true の場合は棒グラフを付ける）
+fn write_counts(out: &mut impl Write, counts: &[(String, usize)], histogram: bool) -> io::Result<()> {
+    let name_width = counts.iter().map(|(name, _)| name.chars().count()).max().unwrap_or(0);
+    let count_width = counts.iter().map(|(_, count)| count.to_string().len()).max().unwrap_or(0);
+    let max_count = counts.iter().map(|(_, count)| *count).max().unwrap_or(0);
+
+    for (name, count) in counts {
+        write!(out, "  {:<name_width$}  {:>count_width$}", name, count)?;
+        if histogram && max_count > 0 {
+            let bar_len = (count * HISTOGRAM_WIDTH).div_ceil(max_count);
+            write!(out, "  {}", HISTOGRAM_BAR.repeat(bar_len))?;
+        }
+        writeln!(out)?;
+    }
+
+    Ok(())
+}
+
+/// 期間を日数で表示
+fn format_days(duration: Duration) -> String {
+    format!("{:.1}", duration.as_secs_f64() / SECS_PER_DAY)
+}
+
+/// レスポンスを出力
+fn output_response(processed_response: &str, options: &Options) -> Result<()> {
+    if let Some(group_by) = options.group_by
+        && matches!(options.format, Format::Csv | Format::Table)
+    {
+        let crts = parse_crts(processed_response)?;
+        return output_groups(&group_crts(&crts, group_by), group_by, options);
+    }
+
+    match options.format {
+        Format::Csv => {
+            let crts = parse_crts(processed_response)?;
+            let active_columns = get_active_columns(options);
+
+            let rows: Vec<Vec<String>> = build_records(&crts, options)
+                .iter()
+                .map(|record| build_csv_row(record, &active_columns))
+                .collect();
+
+            write_csv(&build_csv_header(&active_columns), rows, options)?;
+        }
+        Format::Table => {
+            let crts = parse_crts(processed_response)?;
+            let active_columns = get_active_columns(options);
+
+            let table: Vec<Vec<CellStruct>> = build_records(&crts, options)
+                .iter()
+                .map(|record| build_table_row(record, &active_columns))
+                .collect();
+
+            print_table(|| build_table_header(&active_columns), table, options)?;
+        }
+        Format::Json => {
+            let crts = parse_crts(processed_response)?;
+            let active_columns = get_active_columns(options);
+
+            let records = build_records(&crts, options);
+            check_row_limit(records.len(), options)?;
+
+            let objects: Vec<Value> = records
+                .iter()
+                .map(|record| build_json_object(record, &active_columns))
+                .collect();
+
+            let mut stdout = io::stdout().lock();
+            serde_json::to_writer_pretty(&mut stdout, &objects).map_err(Error::output)?;
+            writeln!(stdout).map_err(Error::output)?;
+        }
+        Format::Jsonl => {
+            let crts = parse_crts(processed_response)?;
+            let active_columns = get_active_columns(options);
+
+            let records = build_records(&crts, options);
+            check_row_limit(records.len(), options)?;
+
+            let mut stdout = io::stdout().lock();
+            for record in records {
+                let object = build_json_object(&record, &active_columns);
+                serde_json::to_writer(&mut stdout, &object).map_err(Error::output)?;
+                writeln!(stdout).map_err(Error::output)?;
+            }
+        }
+        Format::Stats => {
+            let crts = parse_crts(processed_response)?;
+            let stats = Stats::from_crts(&crts, options.config.filters.reference_time());
+
+            print_stats(&stats).map_err(Error::output)?;
+        }
+        Format::Raw => {
+            if let Ok(rows) = from_str::<Vec<IgnoredAny>>(processed_response) {
+                check_row_limit(rows.len(), options)?;
+            }
+
+            println!("{}", processed_response);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn crt_value(
+        id: u64,
+        common_name: &str,
+        entry_timestamp: Option<&str>,
+        not_after: &str,
+    ) -> Value {
+        json!({
+            "id": id,
+            "common_name": common_name,
+            "entry_timestamp": entry_timestamp,
+            "issuer_ca_id": 1,
+            "issuer_name": "C=GB, O=Sectigo Limited",
+            "name_value": common_name,
+            "not_after": not_after,
+            "not_before": "2025-12-16T00:00:00",
+            "result_count": 1,
+            "serial_number": format!("{:02x}", id),
+        })
+    }
+
+    fn crt(id: u64, common_name: &str, entry_timestamp: Option<&str>, not_after: &str) -> Crt {
+        serde_json::from_value(crt_value(id, common_name, entry_timestamp, not_after)).unwrap()
+    }
+
+    fn sort_columns(keys: &[&str]) -> Vec<(&'static ColumnDefinition, SortOrder)> {
+        let options = Options {
+            sort_keys: keys
+                .iter()
+                .map(|key| SortKey::parse(key).unwrap())
+                .collect(),
+            ..Options::default()
+        };
+        get_sort_columns(&options).unwrap()
+    }
+
+    fn sorted_ids(mut crts: Vec<Crt>, keys: &[&str]) -> Vec<u64> {
+        let sort_columns = sort_columns(keys);
+        crts.sort_by(|a, b| compare_crts(a, b, &sort_columns));
+        crts.iter().map(|crt| crt.id).collect()
+    }
+
+    fn select(values: Vec<Value>, options: &Options) -> Vec<Value> {
+        let body = Value::Array(values.clone()).to_string();
+        select_values(&body, values, options).unwrap()
+    }
+
+    #[test]
+    fn compare_uses_later_keys_to_break_ties() {
+        let crts = vec![
+            crt(1, "b.example.com", None, "2026-03-01T00:00:00"),
+            crt(2, "a.example.com", None, "2026-03-01T00:00:00"),
+            crt(3, "c.example.com", None, "2026-01-01T00:00:00"),
+        ];
+
+        assert_eq!(sorted_ids(crts, &["not_after", "common_name"]), [3, 2, 1]);
+    }
+
+    #[test]
+    fn compare_reverses_descending_keys() {
+        let crts = vec![
+            crt(1, "b.example.com", None, "2026-03-01T00:00:00"),
+            crt(2, "a.example.com", None, "2026-03-01T00:00:00"),
+            crt(3, "c.example.com", None, "2026-01-01T00:00:00"),
+        ];
+
+        assert_eq!(
+            sorted_ids(crts, &["not_after:desc", "common_name:desc"]),
+            [1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn compare_keeps_input_order_for_equal_keys() {
+        let crts = vec![
+            crt(3, "a.example.com", None, "2026-03-01T00:00:00"),
+            crt(1, "a.example.com", None, "2026-03-01T00:00:00"),
+            crt(2, "a.example.com", None, "2026-03-01T00:00:00"),
+        ];
+
+        assert_eq!(sorted_ids(crts, &["common_name"]), [3, 1, 2]);
+    }
+
+    #[test]
+    fn compare_sorts_missing_values_first() {
+        let crts = vec![
+            crt(
+                1,
+                "a.example.com",
+                Some("2025-12-16T21:50:43"),
+                "2026-03-01T00:00:00",
+            ),
+            crt(2, "a.example.com", None, "2026-03-01T00:00:00"),
+        ];
+
+        assert_eq!(sorted_ids(crts.clone(), &["entry_timestamp"]), [2, 1]);
+        assert_eq!(sorted_ids(crts, &["entry_timestamp:desc"]), [1, 2]);
+    }
+
+    #[test]
+    fn unknown_sort_column_is_rejected() {
+        let options = Options {
+            sort_keys: vec![SortKey::parse("nope").unwrap()],
+            ..Options::default()
+        };
+
+        assert!(matches!(get_sort_columns(&options), Err(Error::Config(_))));
+    }
+
+    #[test]
+    fn select_keeps_rows_with_duplicate_ids() {
+        let mut values = vec![
+            crt_value(1, "b.example.com", None, "2026-03-01T00:00:00"),
+            crt_value(1, "b.example.com", None, "2026-03-01T00:00:00"),
+            crt_value(2, "a.example.com", None, "2026-03-01T00:00:00"),
+        ];
+        values[1]["result_count"] = json!(2);
+        let options = Options {
+            sort_keys: vec![SortKey::parse("common_name").unwrap()],
+            ..Options::default()
+        };
+
+        let selected = select(values.clone(), &options);
+
+        assert_eq!(
+            selected,
+            [values[2].clone(), values[0].clone(), values[1].clone()]
+        );
+    }
+
+    #[test]
+    fn select_applies_offset_and_limit_after_sort() {
+        let values = vec![
+            crt_value(3, "c.example.com", None, "2026-03-01T00:00:00"),
+            crt_value(1, "a.example.com", None, "2026-03-01T00:00:00"),
+            crt_value(2, "b.example.com", None, "2026-03-01T00:00:00"),
+        ];
+        let options = Options {
+            sort_keys: vec![SortKey::parse("id:desc").unwrap()],
+            offset: 1,
+            limit: Some(1),
+            ..Options::default()
+        };
+
+        assert_eq!(select(values.clone(), &options), [values[2].clone()]);
+    }
+
+    #[test]
+    fn row_limit_counts_all_rows_even_with_page_size() {
+        let options = Options {
+            max_rows: 10,
+            page_size: Some(5),
+            ..Options::default()
+        };
+
+        assert!(check_row_limit(10, &options).is_ok());
+        assert!(matches!(check_row_limit(11, &options), Err(Error::Output(_))));
+    }
+
+    #[test]
+    fn row_limit_is_disabled_by_zero_or_force() {
+        let disabled = Options {
+            max_rows: 0,
+            ..Options::default()
+        };
+        let forced = Options {
+            max_rows: 1,
+            force: true,
+            ..Options::default()
+        };
+
+        assert!(check_row_limit(usize::MAX, &disabled).is_ok());
+        assert!(check_row_limit(2, &forced).is_ok());
+    }
+}
@@ -0,0 +1,143 @@
+use crate::error::{Error, Result};
+use regex::Regex;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+// エラーメッセージ
+const ERROR_INVALID_REGEX: &str = "Invalid regular expression '{1}': {2}";
+
+/// 名前の絞り込みに使用するパターン（正規表現、またはそれに変換したグロブ）
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    regex: Regex,
+}
+
+impl Pattern {
+    /// 正規表現として解析
+    pub fn regex(raw: &str) -> Result<Self> {
+        let regex = Regex::new(raw).map_err(|e| {
+            Error::Config(
+                ERROR_INVALID_REGEX
+                    .replace("{1}", raw)
+                    .replace("{2}", &e.to_string()),
+            )
+        })?;
+
+        Ok(Self { regex })
+    }
+
+    /// グロブ（`*` と `?`、大文字小文字を区別しない完全一致）として解析
+    pub fn glob(raw: &str) -> Result<Self> {
+        let mut translated = String::from("(?i)^");
+        for c in raw.chars() {
+            match c {
+                '*' => translated.push_str(".*"),
+                '?' => translated.push('.'),
+                c => translated.push_str(&regex::escape(&c.to_string())),
+            }
+        }
+        translated.push('$');
+
+        Self::regex(&translated)
+    }
+
+    /// 正規表現の文字列
+    pub fn as_str(&self) -> &str {
+        self.regex.as_str()
+    }
+
+    /// 文字列がパターンに一致するかどうか
+    pub fn is_match(&self, text: &str) -> bool {
+        self.regex.is_match(text)
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for Pattern {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Pattern {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let raw = String::deserialize(deserializer)?;
+        let regex = Regex::new(&raw).map_err(|e| {
+            serde::de::Error::custom(
+                ERROR_INVALID_REGEX
+                    .replace("{1}", &raw)
+                    .replace("{2}", &e.to_string()),
+            )
+        })?;
+
+        Ok(Self { regex })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob_matches_whole_name_case_insensitively() {
+        let pattern = Pattern::glob("*.Example.com").unwrap();
+
+        assert!(pattern.is_match("www.example.com"));
+        assert!(pattern.is_match("a.b.EXAMPLE.COM"));
+        assert!(!pattern.is_match("example.com"));
+        assert!(!pattern.is_match("www.example.com.evil.test"));
+    }
+
+    #[test]
+    fn glob_question_mark_matches_one_character() {
+        let pattern = Pattern::glob("api?.example.com").unwrap();
+
+        assert!(pattern.is_match("api1.example.com"));
+        assert!(!pattern.is_match("api.example.com"));
+        assert!(!pattern.is_match("api12.example.com"));
+    }
+
+    #[test]
+    fn glob_escapes_regex_metacharacters() {
+        let pattern = Pattern::glob("a+b.(test)").unwrap();
+
+        assert!(pattern.is_match("a+b.(test)"));
+        assert!(!pattern.is_match("aab.(test)"));
+        assert!(!pattern.is_match("a+bx(test)"));
+    }
+
+    #[test]
+    fn regex_matches_anywhere_unless_anchored() {
+        let pattern = Pattern::regex("stag(e|ing)").unwrap();
+
+        assert!(pattern.is_match("api.staging.example.com"));
+        assert!(pattern.is_match("stage.example.com"));
+        assert!(!pattern.is_match("Staging.example.com"));
+        assert!(
+            !Pattern::regex("^staging")
+                .unwrap()
+                .is_match("api.staging.example.com")
+        );
+    }
+
+    #[test]
+    fn invalid_regex_is_a_config_error() {
+        assert!(matches!(Pattern::regex("(unclosed"), Err(Error::Config(_))));
+    }
+
+    #[test]
+    fn round_trips_through_serde_as_regex_string() {
+        let pattern = Pattern::glob("*.example.com").unwrap();
+
+        let serialized = serde_json::to_string(&pattern).unwrap();
+        let deserialized: Pattern = serde_json::from_str(&serialized).unwrap();
+
+        assert_eq!(deserialized.as_str(), pattern.as_str());
+        assert!(serde_json::from_str::<Pattern>("\"(\"").is_err());
+    }
+}
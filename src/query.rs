@@ -0,0 +1,484 @@
+use crate::error::{Error, Result};
+use crate::search_term::{SearchTerm, encode_query_value};
+use serde::{Deserialize, Serialize};
+
+// crt.sh のエンドポイント
+const BASE_URL: &str = "https://crt.sh/";
+
+// 固定パラメータ
+const PARAM_OUTPUT: &str = "output";
+const PARAM_EXCLUDE: &str = "exclude";
+const PARAM_DEDUPLICATE: &str = "deduplicate";
+const PARAM_MATCH: &str = "match";
+const OUTPUT_JSON: &str = "json";
+const EXCLUDE_EXPIRED: &str = "expired";
+const DEDUPLICATE_YES: &str = "Y";
+
+// ハッシュ値の長さ（16進数の文字数）
+const SHA1_HEX_LEN: usize = 40;
+const SHA256_HEX_LEN: usize = 64;
+
+// エラーメッセージ
+const ERROR_NO_SEARCH: &str = "A search parameter is required (e.g. --hostname, --identity, --sha256)";
+const ERROR_MULTIPLE_SEARCH: &str = "Only one search parameter can be used at a time: {}";
+const ERROR_INVALID_TERM: &str = "Invalid search value for '{1}': {2}";
+const ERROR_TEXT_SEARCH_HINT: &str = "{} (use --match FTS to search free text)";
+const ERROR_INVALID_HEX: &str = "'{1}' must be a hexadecimal string: {2}";
+const ERROR_INVALID_HEX_LEN: &str = "'{1}' must be {2} hexadecimal characters long";
+const ERROR_INVALID_CAID: &str = "'caid' must be a positive integer: {}";
+const ERROR_MATCH_NOT_ALLOWED: &str = "--match cannot be combined with '{}' search";
+const ERROR_WILDCARD_NOT_ALLOWED: &str = "Wildcard '%' cannot be used with '{1}' (match={2})";
+const ERROR_UNKNOWN_MATCH: &str = "Unknown match mode: {} (expected =, LIKE, ILIKE or FTS)";
+
+/// crt.sh の検索パラメータ
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SearchField {
+    Q,
+    Identity,
+    CommonName,
+    Organization,
+    Serial,
+    Sha1,
+    Sha256,
+    SpkiSha256,
+    CaId,
+}
+
+impl SearchField {
+    /// クエリパラメータ名
+    pub fn param(&self) -> &'static str {
+        match self {
+            SearchField::Q => "q",
+            SearchField::Identity => "identity",
+            SearchField::CommonName => "CN",
+            SearchField::Organization => "O",
+            SearchField::Serial => "serial",
+            SearchField::Sha1 => "sha1",
+            SearchField::Sha256 => "sha256",
+            SearchField::SpkiSha256 => "spkisha256",
+            SearchField::CaId => "caid",
+        }
+    }
+
+    /// テキスト検索（match / ワイルドカードが使える）かどうか
+    pub fn is_text(&self) -> bool {
+        matches!(
+            self,
+            SearchField::Q
+                | SearchField::Identity
+                | SearchField::CommonName
+                | SearchField::Organization
+        )
+    }
+
+    /// ホスト名として解釈し得るかどうか
+    fn accepts_hostname(&self) -> bool {
+        matches!(
+            self,
+            SearchField::Q | SearchField::Identity | SearchField::CommonName
+        )
+    }
+}
+
+/// match パラメータ
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MatchMode {
+    Exact,
+    Like,
+    ILike,
+    Fts,
+}
+
+impl MatchMode {
+    /// クエリパラメータの値
+    pub fn param(&self) -> &'static str {
+        match self {
+            MatchMode::Exact => "=",
+            MatchMode::Like => "LIKE",
+            MatchMode::ILike => "ILIKE",
+            MatchMode::Fts => "FTS",
+        }
+    }
+
+    /// ワイルドカード '%' を解釈するかどうか
+    fn allows_wildcard(&self) -> bool {
+        matches!(self, MatchMode::Like | MatchMode::ILike)
+    }
+}
+
+impl std::str::FromStr for MatchMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "=" | "EXACT" => Ok(MatchMode::Exact),
+            "LIKE" => Ok(MatchMode::Like),
+            "ILIKE" => Ok(MatchMode::ILike),
+            "FTS" => Ok(MatchMode::Fts),
+            _ => Err(ERROR_UNKNOWN_MATCH.replace("{}", s)),
+        }
+    }
+}
+
+/// 検証済みの crt.sh クエリ
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Query {
+    field: SearchField,
+    term: SearchTerm,
+    exclude_expired: bool,
+    deduplicate: bool,
+    match_mode: Option<MatchMode>,
+}
+
+impl Query {
+    pub fn builder() -> QueryBuilder {
+        QueryBuilder::default()
+    }
+
+    pub fn field(&self) -> SearchField {
+        self.field
+    }
+
+    pub fn term(&self) -> &SearchTerm {
+        &self.term
+    }
+
+    /// JSON 出力用の URL を作成
+    pub fn to_url(&self) -> String {
+        let mut params: Vec<(&str, String)> = vec![(self.field.param(), self.term.encoded())];
+
+        if self.exclude_expired {
+            params.push((PARAM_EXCLUDE, encode_query_value(EXCLUDE_EXPIRED)));
+        }
+
+        if self.deduplicate {
+            params.push((PARAM_DEDUPLICATE, encode_query_value(DEDUPLICATE_YES)));
+        }
+
+        if let Some(match_mode) = self.match_mode {
+            params.push((PARAM_MATCH, encode_query_value(match_mode.param())));
+        }
+
+        params.push((PARAM_OUTPUT, encode_query_value(OUTPUT_JSON)));
+
+        let query_string: Vec<String> = params
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect();
+
+        format!("{}?{}", BASE_URL, query_string.join("&"))
+    }
+}
+
+/// クエリビルダー
+#[derive(Debug, Clone, Default)]
+pub struct QueryBuilder {
+    searches: Vec<(SearchField, String)>,
+    exclude_expired: bool,
+    deduplicate: bool,
+    match_mode: Option<MatchMode>,
+}
+
+impl QueryBuilder {
+    /// 検索パラメータを追加
+    pub fn search(mut self, field: SearchField, value: impl Into<String>) -> Self {
+        self.searches.push((field, value.into()));
+        self
+    }
+
+    /// 期限切れの証明書を除外
+    pub fn exclude_expired(mut self, exclude_expired: bool) -> Self {
+        self.exclude_expired = exclude_expired;
+        self
+    }
+
+    /// プレ証明書と証明書の重複を除外
+    pub fn deduplicate(mut self, deduplicate: bool) -> Self {
+        self.deduplicate = deduplicate;
+        self
+    }
+
+    /// マッチ方法を指定
+    pub fn match_mode(mut self, match_mode: Option<MatchMode>) -> Self {
+        self.match_mode = match_mode;
+        self
+    }
+
+    /// 検索パラメータが指定されているかどうか
+    pub fn has_search(&self) -> bool {
+        !self.searches.is_empty()
+    }
+
+    /// 組み合わせを検証してクエリを作成
+    pub fn build(self) -> Result<Query> {
+        let (field, value) = match self.searches.as_slice() {
+            [] => return Err(Error::InvalidQuery(ERROR_NO_SEARCH.to_string())),
+            [search] => search.clone(),
+            searches => {
+                let names: Vec<&str> = searches.iter().map(|(field, _)| field.param()).collect();
+                return Err(Error::InvalidQuery(
+                    ERROR_MULTIPLE_SEARCH.replace("{}", &names.join(", ")),
+                ));
+            }
+        };
+
+        let term = parse_term(field, &value, self.match_mode)?;
+        validate_match_mode(field, &term, self.match_mode)?;
+
+        Ok(Query {
+            field,
+            term,
+            exclude_expired: self.exclude_expired,
+            deduplicate: self.deduplicate,
+            match_mode: self.match_mode,
+        })
+    }
+}
+
+/// 検索値を検証して検索語を作成
+///
+/// q（--hostname）は match=FTS を指定した場合のみ自由入力として扱い、それ以外はホスト名として検証する。
+/// identity・CN は空白を含む値（組織名など）を自由入力として扱う。
+fn parse_term(field: SearchField, value: &str, match_mode: Option<MatchMode>) -> Result<SearchTerm> {
+    let is_text = !field.accepts_hostname()
+        || match_mode == Some(MatchMode::Fts)
+        || (field != SearchField::Q && value.trim().contains(char::is_whitespace));
+
+    let term = if is_text {
+        SearchTerm::text(value)
+    } else {
+        SearchTerm::parse(value)
+    }
+    .map_err(|e| match e {
+        Error::InvalidQuery(message) => {
+            let message = ERROR_INVALID_TERM
+                .replace("{1}", field.param())
+                .replace("{2}", &message);
+            if field == SearchField::Q {
+                Error::InvalidQuery(ERROR_TEXT_SEARCH_HINT.replace("{}", &message))
+            } else {
+                Error::InvalidQuery(message)
+            }
+        }
+        e => e,
+    })?;
+
+    validate_value(field, term.as_str())?;
+
+    Ok(term)
+}
+
+/// 検索パラメータごとの形式を検証
+fn validate_value(field: SearchField, value: &str) -> Result<()> {
+    match field {
+        SearchField::Sha1 => validate_hex(field, value, Some(SHA1_HEX_LEN)),
+        SearchField::Sha256 | SearchField::SpkiSha256 => {
+            validate_hex(field, value, Some(SHA256_HEX_LEN))
+        }
+        SearchField::Serial => validate_hex(field, value, None),
+        SearchField::CaId => match value.parse::<u64>() {
+            Ok(id) if id > 0 => Ok(()),
+            _ => Err(Error::InvalidQuery(ERROR_INVALID_CAID.replace("{}", value))),
+        },
+        _ => Ok(()),
+    }
+}
+
+/// 16進数文字列を検証
+fn validate_hex(
+    field: SearchField,
+    value: &str,
+    expected_len: Option<usize>,
+) -> Result<()> {
+    let hex = value.replace(':', "");
+
+    if hex.is_empty() || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(Error::InvalidQuery(
+            ERROR_INVALID_HEX
+                .replace("{1}", field.param())
+                .replace("{2}", value),
+        ));
+    }
+
+    if let Some(len) = expected_len
+        && hex.len() != len
+    {
+        return Err(Error::InvalidQuery(
+            ERROR_INVALID_HEX_LEN
+                .replace("{1}", field.param())
+                .replace("{2}", &len.to_string()),
+        ));
+    }
+
+    Ok(())
+}
+
+/// match パラメータとの組み合わせを検証
+fn validate_match_mode(
+    field: SearchField,
+    term: &SearchTerm,
+    match_mode: Option<MatchMode>,
+) -> Result<()> {
+    let Some(match_mode) = match_mode else {
+        return Ok(());
+    };
+
+    if !field.is_text() {
+        return Err(Error::InvalidQuery(
+            ERROR_MATCH_NOT_ALLOWED.replace("{}", field.param()),
+        ));
+    }
+
+    if term.has_wildcard() && !match_mode.allows_wildcard() {
+        return Err(Error::InvalidQuery(
+            ERROR_WILDCARD_NOT_ALLOWED
+                .replace("{1}", field.param())
+                .replace("{2}", match_mode.param()),
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search_term::TermKind;
+
+    fn build(field: SearchField, value: &str, match_mode: Option<MatchMode>) -> Result<Query> {
+        Query::builder()
+            .search(field, value)
+            .match_mode(match_mode)
+            .build()
+    }
+
+    #[test]
+    fn hostname_with_whitespace_or_invalid_characters_is_rejected() {
+        for value in ["example com", "example.com other", "exa!mple.com"] {
+            let Err(Error::InvalidQuery(message)) = build(SearchField::Q, value, None) else {
+                panic!("{value} must be rejected");
+            };
+            assert!(message.contains("--match FTS"), "{message}");
+        }
+    }
+
+    #[test]
+    fn hostname_accepts_free_text_with_full_text_search() {
+        let query = build(SearchField::Q, "Example Org", Some(MatchMode::Fts)).unwrap();
+
+        assert_eq!(query.term().kind(), TermKind::Text);
+        assert_eq!(
+            query.to_url(),
+            "https://crt.sh/?q=Example%20Org&match=FTS&output=json"
+        );
+    }
+
+    #[test]
+    fn identity_and_common_name_accept_names_with_whitespace() {
+        for field in [SearchField::Identity, SearchField::CommonName] {
+            let query = build(field, "Example Org", None).unwrap();
+            assert_eq!(query.term().kind(), TermKind::Text);
+        }
+    }
+
+    #[test]
+    fn builds_url_with_optional_parameters() {
+        let query = Query::builder()
+            .search(SearchField::Identity, "%.example.com")
+            .exclude_expired(true)
+            .deduplicate(true)
+            .match_mode(Some(MatchMode::ILike))
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            query.to_url(),
+            "https://crt.sh/?identity=%25.example.com&exclude=expired&deduplicate=Y&match=ILIKE&output=json"
+        );
+    }
+
+    #[test]
+    fn requires_exactly_one_search() {
+        assert!(matches!(
+            Query::builder().build(),
+            Err(Error::InvalidQuery(_))
+        ));
+
+        let Err(Error::InvalidQuery(message)) = Query::builder()
+            .search(SearchField::Q, "example.com")
+            .search(SearchField::Sha1, "00")
+            .build()
+        else {
+            panic!("multiple searches must be rejected");
+        };
+        assert!(message.contains("q, sha1"), "{message}");
+    }
+
+    #[test]
+    fn match_is_rejected_for_non_text_field() {
+        let sha1 = "0".repeat(SHA1_HEX_LEN);
+
+        assert!(matches!(
+            build(SearchField::Sha1, &sha1, Some(MatchMode::Exact)),
+            Err(Error::InvalidQuery(_))
+        ));
+        assert!(build(SearchField::Sha1, &sha1, None).is_ok());
+    }
+
+    #[test]
+    fn wildcard_requires_like_match() {
+        assert!(matches!(
+            build(SearchField::Q, "%.example.com", Some(MatchMode::Exact)),
+            Err(Error::InvalidQuery(_))
+        ));
+        assert!(build(SearchField::Q, "%.example.com", Some(MatchMode::Like)).is_ok());
+        assert!(build(SearchField::Q, "%.example.com", None).is_ok());
+    }
+
+    #[test]
+    fn hex_values_are_checked_for_digits_and_length() {
+        let sha256 = "ab:".repeat(SHA256_HEX_LEN / 2);
+
+        assert!(build(SearchField::Sha256, &sha256, None).is_ok());
+        assert!(build(SearchField::Serial, "04:e3:a1", None).is_ok());
+
+        for (field, value) in [
+            (SearchField::Sha1, "0".repeat(SHA1_HEX_LEN - 1)),
+            (SearchField::Sha256, "0".repeat(SHA1_HEX_LEN)),
+            (SearchField::SpkiSha256, "g".repeat(SHA256_HEX_LEN)),
+            (SearchField::Serial, "xyz".to_string()),
+            (SearchField::Serial, ":".to_string()),
+            (SearchField::Serial, "::".to_string()),
+        ] {
+            assert!(
+                matches!(build(field, &value, None), Err(Error::InvalidQuery(_))),
+                "{field:?} {value}"
+            );
+        }
+    }
+
+    #[test]
+    fn caid_must_be_positive_integer() {
+        assert_eq!(
+            build(SearchField::CaId, "183267", None).unwrap().to_url(),
+            "https://crt.sh/?caid=183267&output=json"
+        );
+
+        for value in ["0", "-1", "abc", "1.5"] {
+            assert!(
+                matches!(
+                    build(SearchField::CaId, value, None),
+                    Err(Error::InvalidQuery(_))
+                ),
+                "{value}"
+            );
+        }
+    }
+
+    #[test]
+    fn parses_match_mode_names() {
+        assert_eq!("=".parse::<MatchMode>(), Ok(MatchMode::Exact));
+        assert_eq!("ilike".parse::<MatchMode>(), Ok(MatchMode::ILike));
+        assert!("regex".parse::<MatchMode>().is_err());
+    }
+}
@@ -0,0 +1,392 @@
+use crate::client::Config;
+use chrono::{DateTime, Utc};
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::{Duration, Instant, SystemTime};
+
+// リトライ関連
+const RETRY_BACKOFF_MULTIPLIER: f64 = 2.0;
+
+// HTTPステータスコード
+const SERVER_ERROR_START: u16 = 500;
+const SERVER_ERROR_END: u16 = 599;
+const TOO_MANY_REQUESTS: u16 = 429;
+const REQUEST_TIMEOUT: u16 = 408;
+
+/// リトライ時に通知されるイベント
+#[derive(Debug)]
+pub enum RetryEvent<'a> {
+    /// リトライ対象のステータスコードを受信
+    Status {
+        attempt: u32,
+        status: reqwest::StatusCode,
+        delay: Duration,
+    },
+    /// リクエストエラーが発生
+    Error {
+        attempt: u32,
+        error: &'a reqwest::Error,
+        delay: Duration,
+    },
+}
+
+/// リトライ時に呼び出されるフック
+pub type RetryHook = Box<dyn Fn(&RetryEvent) + Send + Sync>;
+
+/// リトライポリシー（同期・非同期クライアントで共通）
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    retry: u32,
+    retry_delay: f64,
+    max_delay: f64,
+    jitter: bool,
+    budget: Option<f64>,
+}
+
+impl RetryPolicy {
+    pub fn new(retry: u32, retry_delay: f64) -> Self {
+        Self {
+            retry,
+            retry_delay,
+            max_delay: f64::INFINITY,
+            jitter: false,
+            budget: None,
+        }
+    }
+
+    /// 設定からリトライポリシーを作成
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(config.retry, config.retry_delay)
+            .max_delay(config.retry_max_delay)
+            .jitter(config.retry_jitter)
+            .budget(config.retry_budget)
+    }
+
+    /// 1 回の待機時間の上限（秒）
+    pub fn max_delay(mut self, max_delay: f64) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// 待機時間を 0 からバックオフ値までの乱数にする（full jitter）
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// 初回のリクエストからリトライを打ち切るまでの合計時間（秒）
+    pub fn budget(mut self, budget: Option<f64>) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    /// 最大試行回数（初回を含む）
+    pub fn max_attempts(&self) -> u32 {
+        self.retry + 1
+    }
+
+    /// まだリトライできるかどうか
+    pub fn can_retry(&self, current_attempt: u32) -> bool {
+        current_attempt < self.max_attempts()
+    }
+
+    /// ステータスコードによるリトライ判定
+    pub fn should_retry_for_status(&self, status: reqwest::StatusCode) -> bool {
+        matches!(
+            status.as_u16(),
+            SERVER_ERROR_START..=SERVER_ERROR_END | TOO_MANY_REQUESTS | REQUEST_TIMEOUT
+        )
+    }
+
+    /// 指定した試行の後に待機する時間（上限付きの指数バックオフ、jitter 指定時は乱数）
+    pub fn backoff_delay(&self, current_attempt: u32) -> Duration {
+        let backoff_delay = (self.retry_delay
+            * RETRY_BACKOFF_MULTIPLIER.powi(current_attempt.saturating_sub(1) as i32))
+        .min(self.max_delay);
+
+        let backoff_delay = if self.jitter {
+            backoff_delay * random_fraction()
+        } else {
+            backoff_delay
+        };
+
+        seconds_to_duration(backoff_delay)
+    }
+
+    /// 次のリトライまでの待機時間（リトライしない場合は None）
+    ///
+    /// Retry-After が指定されている場合はバックオフの代わりにその値を使用する。max_delay は
+    /// バックオフにのみ適用し、Retry-After が max_delay を超える場合はサーバーの指定より早く
+    /// 再送しないようリトライしない。待機後に合計時間の上限を超える場合（Duration で表せない
+    /// 場合を含む）もリトライしない。
+    pub fn next_delay(
+        &self,
+        current_attempt: u32,
+        elapsed: Duration,
+        retry_after: Option<Duration>,
+    ) -> Option<Duration> {
+        if !self.can_retry(current_attempt) {
+            return None;
+        }
+
+        let delay = match retry_after {
+            Some(retry_after) if retry_after > seconds_to_duration(self.max_delay) => return None,
+            Some(retry_after) => retry_after,
+            None => self.backoff_delay(current_attempt),
+        };
+
+        match self.budget {
+            Some(budget) => elapsed
+                .checked_add(delay)
+                .filter(|total| total.as_secs_f64() <= budget)
+                .map(|_| delay),
+            None => Some(delay),
+        }
+    }
+}
+
+/// Retry-After ヘッダーの待機時間（秒数・HTTP-date の両形式）
+pub fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    let delay = date.with_timezone(&Utc) - Utc::now();
+
+    Some(delay.to_std().unwrap_or_default())
+}
+
+/// 秒数を Duration に変換（負数・非有限値は 0、上限なしは最大値）
+fn seconds_to_duration(seconds: f64) -> Duration {
+    Duration::try_from_secs_f64(seconds.max(0.0)).unwrap_or(Duration::MAX)
+}
+
+/// リトライループの状態（同期・非同期クライアントで共通の判定・通知・計時）
+pub(crate) struct RetryState<'a> {
+    policy: RetryPolicy,
+    retry_hook: Option<&'a RetryHook>,
+    current_attempt: u32,
+    overall_start: Instant,
+    request_start: Instant,
+}
+
+impl<'a> RetryState<'a> {
+    pub(crate) fn new(config: &Config, retry_hook: Option<&'a RetryHook>) -> Self {
+        let now = Instant::now();
+
+        Self {
+            policy: RetryPolicy::from_config(config),
+            retry_hook,
+            current_attempt: 0,
+            overall_start: now,
+            request_start: now,
+        }
+    }
+
+    /// 次の試行を開始
+    pub(crate) fn begin_attempt(&mut self) {
+        self.current_attempt += 1;
+        self.request_start = Instant::now();
+    }
+
+    /// リトライ対象のステータスであればフックに通知して待機時間を返す
+    pub(crate) fn retry_for_status(
+        &self,
+        status: reqwest::StatusCode,
+        headers: &HeaderMap,
+    ) -> Option<Duration> {
+        if !self.policy.should_retry_for_status(status) {
+            return None;
+        }
+
+        let delay = self.policy.next_delay(
+            self.current_attempt,
+            self.overall_start.elapsed(),
+            retry_after(headers),
+        )?;
+        notify_retry(
+            self.retry_hook,
+            &RetryEvent::Status {
+                attempt: self.current_attempt,
+                status,
+                delay,
+            },
+        );
+
+        Some(delay)
+    }
+
+    /// リクエストエラーでリトライする場合はフックに通知して待機時間を返す
+    pub(crate) fn retry_for_error(&self, error: &reqwest::Error) -> Option<Duration> {
+        let delay =
+            self.policy
+                .next_delay(self.current_attempt, self.overall_start.elapsed(), None)?;
+        notify_retry(
+            self.retry_hook,
+            &RetryEvent::Error {
+                attempt: self.current_attempt,
+                error,
+                delay,
+            },
+        );
+
+        Some(delay)
+    }
+
+    /// 現在の試行の開始からの経過時間
+    pub(crate) fn request_elapsed(&self) -> Duration {
+        self.request_start.elapsed()
+    }
+
+    /// 最初の試行の開始からの経過時間
+    pub(crate) fn total_elapsed(&self) -> Duration {
+        self.overall_start.elapsed()
+    }
+}
+
+/// 0 以上 1 以下の乱数（外部クレートを使わずにハッシュのランダムシードから生成）
+fn random_fraction() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    let nanos = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .subsec_nanos();
+    hasher.write_u32(nanos);
+
+    hasher.finish() as f64 / u64::MAX as f64
+}
+
+/// リトライをフックに通知
+fn notify_retry(retry_hook: Option<&RetryHook>, event: &RetryEvent) {
+    if let Some(hook) = retry_hook {
+        hook(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn next_delay_without_budget_allows_unbounded_delay() {
+        let policy = RetryPolicy::new(3, f64::INFINITY);
+
+        assert_eq!(
+            policy.next_delay(1, Duration::from_secs(1), None),
+            Some(Duration::MAX)
+        );
+    }
+
+    #[test]
+    fn next_delay_treats_overflowing_total_as_over_budget() {
+        let policy = RetryPolicy::new(3, f64::INFINITY).budget(Some(60.0));
+
+        assert_eq!(policy.next_delay(1, Duration::from_secs(1), None), None);
+        assert_eq!(
+            policy.next_delay(1, Duration::from_secs(1), Some(Duration::MAX)),
+            None
+        );
+    }
+
+    #[test]
+    fn next_delay_uses_retry_after_without_capping() {
+        let policy = RetryPolicy::new(3, 1.0).max_delay(10.0);
+
+        assert_eq!(
+            policy.next_delay(1, Duration::ZERO, Some(Duration::from_secs(10))),
+            Some(Duration::from_secs(10))
+        );
+        assert_eq!(
+            policy.next_delay(1, Duration::ZERO, Some(Duration::from_secs(11))),
+            None
+        );
+    }
+
+    #[test]
+    fn next_delay_gives_up_when_retry_after_exceeds_budget() {
+        let policy = RetryPolicy::new(3, 1.0).budget(Some(30.0));
+
+        assert_eq!(
+            policy.next_delay(1, Duration::from_secs(10), Some(Duration::from_secs(20))),
+            Some(Duration::from_secs(20))
+        );
+        assert_eq!(
+            policy.next_delay(1, Duration::from_secs(11), Some(Duration::from_secs(20))),
+            None
+        );
+    }
+
+    #[test]
+    fn backoff_delay_is_capped_by_max_delay() {
+        let policy = RetryPolicy::new(5, 1.0).max_delay(3.0);
+
+        let delays: Vec<Duration> = (1..=4)
+            .map(|attempt| policy.backoff_delay(attempt))
+            .collect();
+
+        assert_eq!(delays, [1, 2, 3, 3].map(Duration::from_secs));
+    }
+
+    #[test]
+    fn jittered_backoff_stays_within_capped_delay() {
+        let policy = RetryPolicy::new(5, 1.0).max_delay(3.0).jitter(true);
+
+        for attempt in 1..=4 {
+            for _ in 0..100 {
+                assert!(policy.backoff_delay(attempt) <= Duration::from_secs(3));
+            }
+        }
+
+        for _ in 0..100 {
+            assert!(policy.backoff_delay(1) <= Duration::from_secs(1));
+        }
+    }
+
+    #[test]
+    fn retry_after_parses_delta_seconds() {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, "120".parse().unwrap());
+
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(120)));
+    }
+
+    #[test]
+    fn retry_after_parses_http_date() {
+        let date = (Utc::now() + chrono::Duration::seconds(90)).to_rfc2822();
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, date.parse().unwrap());
+
+        let delay = retry_after(&headers).unwrap();
+        assert!(delay > Duration::from_secs(85) && delay <= Duration::from_secs(90));
+
+        headers.insert(
+            RETRY_AFTER,
+            "Wed, 21 Oct 2015 07:28:00 GMT".parse().unwrap(),
+        );
+        assert_eq!(retry_after(&headers), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn retry_after_ignores_missing_or_invalid_values() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_after(&headers), None);
+
+        headers.insert(RETRY_AFTER, "soon".parse().unwrap());
+        assert_eq!(retry_after(&headers), None);
+    }
+
+    #[test]
+    fn next_delay_respects_budget() {
+        let policy = RetryPolicy::new(3, 1.0).budget(Some(10.0));
+
+        assert_eq!(
+            policy.next_delay(1, Duration::from_secs(5), None),
+            Some(Duration::from_secs(1))
+        );
+        assert_eq!(policy.next_delay(1, Duration::from_secs(10), None), None);
+    }
+}
@@ -0,0 +1,321 @@
+use crate::error::{Error, Result};
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, utf8_percent_encode};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::net::IpAddr;
+
+// crt.sh のワイルドカード
+const WILDCARD: char = '%';
+const LABEL_WILDCARD: char = '*';
+
+// ホスト名の制限
+const MAX_LABEL_LEN: usize = 63;
+const MAX_HOSTNAME_LEN: usize = 253;
+
+// パーセントエンコードしない文字（RFC 3986 の unreserved）
+const QUERY_VALUE: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+// エラーメッセージ
+const ERROR_EMPTY_TERM: &str = "Search term must not be empty";
+const ERROR_CONTROL_CHAR: &str = "Search term contains a control character: {}";
+const ERROR_EMPTY_LABEL: &str = "Hostname '{}' contains an empty label";
+const ERROR_LABEL_TOO_LONG: &str = "Label '{1}' in hostname '{2}' is longer than 63 characters";
+const ERROR_HOSTNAME_TOO_LONG: &str = "Hostname '{}' is longer than 253 characters";
+const ERROR_INVALID_CHAR: &str = "Hostname '{1}' contains an invalid character '{2}'";
+const ERROR_LABEL_HYPHEN: &str = "Label '{1}' in hostname '{2}' must not start or end with '-'";
+const ERROR_IDN_WILDCARD: &str = "Label '{1}' in hostname '{2}' mixes a wildcard with non-ASCII characters";
+const ERROR_IDN: &str = "Label '{1}' in hostname '{2}' is not a valid internationalized domain name";
+
+/// 検索語の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TermKind {
+    /// ホスト名（IDN は punycode に変換済み）
+    Hostname,
+    /// メールアドレス、IP アドレス、組織名などの自由入力
+    Text,
+}
+
+/// 検証済みの検索語
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchTerm {
+    value: String,
+    kind: TermKind,
+}
+
+impl SearchTerm {
+    /// 検索語を解析（メールアドレス・IP アドレス以外はホスト名として検証）
+    ///
+    /// 空白を含む組織名などの自由入力は text で作成する。
+    pub fn parse(raw: &str) -> Result<Self> {
+        let value = validate_text(raw)?;
+
+        if looks_like_text(value) {
+            return Ok(SearchTerm {
+                value: value.to_string(),
+                kind: TermKind::Text,
+            });
+        }
+
+        Self::hostname(value)
+    }
+
+    /// 自由入力の検索語を作成
+    pub fn text(raw: &str) -> Result<Self> {
+        let value = validate_text(raw)?;
+
+        Ok(SearchTerm {
+            value: value.to_string(),
+            kind: TermKind::Text,
+        })
+    }
+
+    /// ホスト名として検証して検索語を作成
+    pub fn hostname(raw: &str) -> Result<Self> {
+        let value = validate_text(raw)?;
+        let hostname = value.strip_suffix('.').unwrap_or(value);
+
+        let labels = hostname
+            .split('.')
+            .map(|label| normalize_label(label, hostname))
+            .collect::<Result<Vec<String>>>()?;
+        let ascii = labels.join(".");
+
+        if ascii.len() > MAX_HOSTNAME_LEN {
+            return Err(Error::InvalidQuery(
+                ERROR_HOSTNAME_TOO_LONG.replace("{}", hostname),
+            ));
+        }
+
+        Ok(SearchTerm {
+            value: ascii,
+            kind: TermKind::Hostname,
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.value
+    }
+
+    pub fn kind(&self) -> TermKind {
+        self.kind
+    }
+
+    /// ワイルドカードを除いたドメイン部分（例: `%.example.com` → `example.com`）
+    pub fn base_domain(&self) -> Option<String> {
+        if self.kind != TermKind::Hostname {
+            return None;
+        }
+
+        let labels: Vec<&str> = self.value.split('.').collect();
+        let start = labels
+            .iter()
+            .rposition(|label| label.contains([WILDCARD, LABEL_WILDCARD]))
+            .map_or(0, |position| position + 1);
+
+        match &labels[start..] {
+            [] => None,
+            labels => Some(labels.join(".")),
+        }
+    }
+
+    /// crt.sh のワイルドカード '%' を含むかどうか
+    pub fn has_wildcard(&self) -> bool {
+        self.value.contains(WILDCARD)
+    }
+
+    /// クエリパラメータ用にパーセントエンコード
+    pub fn encoded(&self) -> String {
+        encode_query_value(&self.value)
+    }
+
+    /// 表示用の値（punycode を Unicode に戻す）
+    pub fn to_unicode(&self) -> String {
+        match self.kind {
+            TermKind::Hostname => idna::domain_to_unicode(&self.value).0,
+            TermKind::Text => self.value.clone(),
+        }
+    }
+}
+
+impl fmt::Display for SearchTerm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.value)
+    }
+}
+
+/// クエリパラメータの値をパーセントエンコード
+pub(crate) fn encode_query_value(value: &str) -> String {
+    utf8_percent_encode(value, QUERY_VALUE).to_string()
+}
+
+/// 共通の検証（空文字・制御文字）
+fn validate_text(raw: &str) -> Result<&str> {
+    let value = raw.trim();
+
+    if value.is_empty() {
+        return Err(Error::InvalidQuery(ERROR_EMPTY_TERM.to_string()));
+    }
+
+    if let Some(c) = value.chars().find(|c| c.is_control()) {
+        return Err(Error::InvalidQuery(
+            ERROR_CONTROL_CHAR.replace("{}", &c.escape_default().to_string()),
+        ));
+    }
+
+    Ok(value)
+}
+
+/// ホスト名ではなくメールアドレス・IP アドレスとして扱う入力かどうか
+fn looks_like_text(value: &str) -> bool {
+    value.contains('@') || value.parse::<IpAddr>().is_ok()
+}
+
+/// ラベルを検証して ASCII（小文字・punycode）に変換
+fn normalize_label(label: &str, hostname: &str) -> Result<String> {
+    if label.is_empty() {
+        return Err(Error::InvalidQuery(
+            ERROR_EMPTY_LABEL.replace("{}", hostname),
+        ));
+    }
+
+    let ascii = if label.is_ascii() {
+        label.to_ascii_lowercase()
+    } else if label.contains([WILDCARD, LABEL_WILDCARD]) {
+        return Err(label_error(ERROR_IDN_WILDCARD, label, hostname));
+    } else {
+        idna::domain_to_ascii(label).map_err(|_| label_error(ERROR_IDN, label, hostname))?
+    };
+
+    if let Some(c) = ascii.chars().find(|c| !is_label_char(*c)) {
+        return Err(Error::InvalidQuery(
+            ERROR_INVALID_CHAR
+                .replace("{1}", hostname)
+                .replace("{2}", &c.to_string()),
+        ));
+    }
+
+    if ascii.starts_with('-') || ascii.ends_with('-') {
+        return Err(label_error(ERROR_LABEL_HYPHEN, label, hostname));
+    }
+
+    if ascii.len() > MAX_LABEL_LEN {
+        return Err(label_error(ERROR_LABEL_TOO_LONG, label, hostname));
+    }
+
+    Ok(ascii)
+}
+
+/// ラベルに関する検証エラーを作成
+fn label_error(message: &str, label: &str, hostname: &str) -> Error {
+    Error::InvalidQuery(message.replace("{1}", label).replace("{2}", hostname))
+}
+
+/// ラベルに使用できる文字かどうか
+fn is_label_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | WILDCARD | LABEL_WILDCARD)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_idn_hostname_to_punycode_and_back() {
+        let term = SearchTerm::parse("Bücher.Example.").unwrap();
+
+        assert_eq!(term.kind(), TermKind::Hostname);
+        assert_eq!(term.as_str(), "xn--bcher-kva.example");
+        assert_eq!(term.encoded(), "xn--bcher-kva.example");
+        assert_eq!(term.to_unicode(), "bücher.example");
+    }
+
+    #[test]
+    fn keeps_punycode_hostname_as_is() {
+        let term = SearchTerm::hostname("xn--bcher-kva.example").unwrap();
+
+        assert_eq!(term.as_str(), "xn--bcher-kva.example");
+        assert_eq!(term.to_unicode(), "bücher.example");
+    }
+
+    #[test]
+    fn encodes_crt_sh_wildcard() {
+        let term = SearchTerm::parse("%.example.com").unwrap();
+
+        assert!(term.has_wildcard());
+        assert_eq!(term.encoded(), "%25.example.com");
+        assert_eq!(term.base_domain().as_deref(), Some("example.com"));
+    }
+
+    #[test]
+    fn accepts_wildcard_label_in_idn_hostname() {
+        let term = SearchTerm::parse("%.bücher.example").unwrap();
+
+        assert_eq!(term.as_str(), "%.xn--bcher-kva.example");
+        assert_eq!(term.base_domain().as_deref(), Some("xn--bcher-kva.example"));
+    }
+
+    #[test]
+    fn accepts_asterisk_wildcard_label() {
+        let term = SearchTerm::parse("*.example.com").unwrap();
+
+        assert!(!term.has_wildcard());
+        assert_eq!(term.encoded(), "%2A.example.com");
+        assert_eq!(term.base_domain().as_deref(), Some("example.com"));
+    }
+
+    #[test]
+    fn wildcard_only_hostname_has_no_base_domain() {
+        assert_eq!(SearchTerm::parse("%").unwrap().base_domain(), None);
+    }
+
+    #[test]
+    fn rejects_wildcard_mixed_with_non_ascii() {
+        assert!(matches!(
+            SearchTerm::parse("bü%cher.example"),
+            Err(Error::InvalidQuery(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_malformed_hostnames() {
+        for raw in [
+            "",
+            "example..com",
+            "-example.com",
+            "exa&mple.com",
+            "example.com#top",
+            "example com",
+            "example.com extra",
+            "exa_mple!.com",
+        ] {
+            assert!(
+                matches!(SearchTerm::parse(raw), Err(Error::InvalidQuery(_))),
+                "{raw}"
+            );
+        }
+    }
+
+    #[test]
+    fn treats_email_and_ip_as_text() {
+        let email = SearchTerm::parse("admin@example.com").unwrap();
+        assert_eq!(email.kind(), TermKind::Text);
+        assert_eq!(email.encoded(), "admin%40example.com");
+
+        assert_eq!(
+            SearchTerm::parse("192.0.2.1").unwrap().kind(),
+            TermKind::Text
+        );
+    }
+
+    #[test]
+    fn text_accepts_whitespace_and_symbols() {
+        let term = SearchTerm::text("Example Org & Co").unwrap();
+
+        assert_eq!(term.kind(), TermKind::Text);
+        assert_eq!(term.encoded(), "Example%20Org%20%26%20Co");
+    }
+}
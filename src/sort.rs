@@ -0,0 +1,88 @@
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+// カラム名と並び順の区切り
+const SORT_DELIMITER: char = ':';
+
+// エラーメッセージ
+const ERROR_INVALID_SORT_ORDER: &str = "Invalid sort order '{1}' for column '{2}' (expected asc or desc)";
+const ERROR_EMPTY_SORT_COLUMN: &str = "Sort column must not be empty";
+
+/// 並び順
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+/// 並べ替えのキー（`column[:asc|desc]`）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SortKey {
+    pub column: String,
+    pub order: SortOrder,
+}
+
+impl SortKey {
+    /// `not_after:desc` のような指定を解析（並び順の省略時は昇順）
+    pub fn parse(raw: &str) -> Result<Self> {
+        let (column, order) = match raw.split_once(SORT_DELIMITER) {
+            Some((column, order)) => (column.trim(), Some(order.trim())),
+            None => (raw.trim(), None),
+        };
+
+        if column.is_empty() {
+            return Err(Error::Config(ERROR_EMPTY_SORT_COLUMN.to_string()));
+        }
+
+        let order = match order.map(str::to_ascii_lowercase).as_deref() {
+            None | Some("asc") => SortOrder::Asc,
+            Some("desc") => SortOrder::Desc,
+            Some(order) => {
+                return Err(Error::Config(
+                    ERROR_INVALID_SORT_ORDER
+                        .replace("{1}", order)
+                        .replace("{2}", column),
+                ));
+            }
+        };
+
+        Ok(SortKey {
+            column: column.to_string(),
+            order,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_defaults_to_ascending() {
+        assert_eq!(
+            SortKey::parse("not_after").unwrap(),
+            SortKey {
+                column: "not_after".to_string(),
+                order: SortOrder::Asc,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_reads_order_case_insensitively_and_trims() {
+        let key = SortKey::parse(" not_after : DESC ").unwrap();
+
+        assert_eq!(key.column, "not_after");
+        assert_eq!(key.order, SortOrder::Desc);
+        assert_eq!(SortKey::parse("id:asc").unwrap().order, SortOrder::Asc);
+    }
+
+    #[test]
+    fn parse_rejects_empty_column_and_unknown_order() {
+        assert!(matches!(SortKey::parse(":desc"), Err(Error::Config(_))));
+        assert!(matches!(SortKey::parse(""), Err(Error::Config(_))));
+        assert!(matches!(SortKey::parse("id:up"), Err(Error::Config(_))));
+        assert!(matches!(SortKey::parse("id:"), Err(Error::Config(_))));
+    }
+}
@@ -0,0 +1,254 @@
+use crate::crt::Crt;
+use crate::identity::Identity;
+use crate::timestamp::Timestamp;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::Duration;
+
+// 月別集計のキーの形式
+const MONTH_FORMAT: &str = "%Y-%m";
+
+/// 有効期間の長さの統計
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValidityStats {
+    pub average: Duration,
+    pub min: Duration,
+    pub max: Duration,
+}
+
+/// 期限切れが最も近い証明書
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UpcomingExpiry {
+    pub id: u64,
+    pub common_name: String,
+    pub not_after: Timestamp,
+}
+
+/// 検索結果の集計
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Stats {
+    pub total: usize,
+    pub unique_serials: usize,
+    pub unique_identities: usize,
+    pub wildcard_certificates: usize,
+    /// 発行者の組織（O、ない場合は issuer_name）ごとの件数（多い順）
+    pub issuer_organizations: Vec<(String, usize)>,
+    /// not_before の月（YYYY-MM）ごとの件数（古い順）
+    pub monthly_issuance: Vec<(String, usize)>,
+    pub validity: Option<ValidityStats>,
+    pub soonest_expiry: Option<UpcomingExpiry>,
+}
+
+impl Stats {
+    /// 証明書の一覧を集計（now は期限切れ判定の基準日時）
+    pub fn from_crts(crts: &[Crt], now: NaiveDateTime) -> Self {
+        let mut serials = HashSet::new();
+        let mut identities = HashSet::new();
+        let mut organizations: HashMap<String, usize> = HashMap::new();
+        let mut months: BTreeMap<String, usize> = BTreeMap::new();
+        let mut wildcard_certificates = 0;
+
+        for crt in crts {
+            serials.insert(crt.serial_number.as_str());
+
+            let crt_identities = crt.identities();
+            if crt_identities.iter().any(Identity::is_wildcard) {
+                wildcard_certificates += 1;
+            }
+            identities.extend(crt_identities);
+
+            let issuer = crt.issuer();
+            let organization = issuer.organization().unwrap_or(&crt.issuer_name);
+            *organizations.entry(organization.to_string()).or_default() += 1;
+
+            let month = crt.not_before.value().format(MONTH_FORMAT).to_string();
+            *months.entry(month).or_default() += 1;
+        }
+
+        let mut issuer_organizations: Vec<(String, usize)> = organizations.into_iter().collect();
+        issuer_organizations.sort_by(|(a_name, a_count), (b_name, b_count)| {
+            b_count.cmp(a_count).then_with(|| a_name.cmp(b_name))
+        });
+
+        let soonest_expiry = crts
+            .iter()
+            .filter(|crt| crt.not_after.value() >= now)
+            .min_by_key(|crt| &crt.not_after)
+            .map(|crt| UpcomingExpiry {
+                id: crt.id,
+                common_name: crt.common_name.clone(),
+                not_after: crt.not_after.clone(),
+            });
+
+        Stats {
+            total: crts.len(),
+            unique_serials: serials.len(),
+            unique_identities: identities.len(),
+            wildcard_certificates,
+            issuer_organizations,
+            monthly_issuance: months.into_iter().collect(),
+            validity: validity_stats(crts),
+            soonest_expiry,
+        }
+    }
+}
+
+/// 有効期間（not_after - not_before）の平均・最小・最大
+fn validity_stats(crts: &[Crt]) -> Option<ValidityStats> {
+    let lengths: Vec<Duration> = crts
+        .iter()
+        .map(|crt| {
+            (crt.not_after.value() - crt.not_before.value())
+                .to_std()
+                .unwrap_or_default()
+        })
+        .collect();
+
+    let min = lengths.iter().min().copied()?;
+    let max = lengths.iter().max().copied()?;
+    let total: Duration = lengths.iter().sum();
+
+    Some(ValidityStats {
+        average: total / lengths.len() as u32,
+        min,
+        max,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const DAY: u64 = 24 * 60 * 60;
+
+    fn crt(
+        id: u64,
+        serial_number: &str,
+        name_value: &str,
+        issuer_name: &str,
+        not_before: &str,
+        not_after: &str,
+    ) -> Crt {
+        serde_json::from_value(json!({
+            "id": id,
+            "common_name": name_value.lines().next().unwrap(),
+            "entry_timestamp": "2025-12-16T21:50:43.225",
+            "issuer_ca_id": 1,
+            "issuer_name": issuer_name,
+            "name_value": name_value,
+            "not_after": not_after,
+            "not_before": not_before,
+            "result_count": 1,
+            "serial_number": serial_number,
+        }))
+        .unwrap()
+    }
+
+    fn now() -> NaiveDateTime {
+        Timestamp::parse("2026-02-01").unwrap().value()
+    }
+
+    fn sample() -> Vec<Crt> {
+        const LE: &str = "C=US, O=Let's Encrypt, CN=R11";
+        const SECTIGO: &str = "C=GB, O=Sectigo Limited, CN=Sectigo CA";
+
+        vec![
+            crt(
+                1,
+                "01",
+                "example.com\n*.example.com",
+                LE,
+                "2026-01-01",
+                "2026-01-31",
+            ),
+            crt(
+                2,
+                "01",
+                "example.com\n*.example.com",
+                LE,
+                "2026-01-01",
+                "2026-01-31",
+            ),
+            crt(
+                3,
+                "02",
+                "www.example.com\nEXAMPLE.com",
+                SECTIGO,
+                "2025-12-15",
+                "2026-03-15",
+            ),
+            crt(
+                4,
+                "03",
+                "api.example.com",
+                "CN=Private Root",
+                "2026-01-20",
+                "2026-02-19",
+            ),
+        ]
+    }
+
+    #[test]
+    fn counts_certificates_serials_identities_and_wildcards() {
+        let stats = Stats::from_crts(&sample(), now());
+
+        assert_eq!(stats.total, 4);
+        assert_eq!(stats.unique_serials, 3);
+        assert_eq!(stats.unique_identities, 4);
+        assert_eq!(stats.wildcard_certificates, 2);
+    }
+
+    #[test]
+    fn groups_issuers_by_organization_most_frequent_first() {
+        let stats = Stats::from_crts(&sample(), now());
+
+        assert_eq!(
+            stats.issuer_organizations,
+            [
+                ("Let's Encrypt".to_string(), 2),
+                ("CN=Private Root".to_string(), 1),
+                ("Sectigo Limited".to_string(), 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn counts_issuance_per_month_oldest_first() {
+        let stats = Stats::from_crts(&sample(), now());
+
+        assert_eq!(
+            stats.monthly_issuance,
+            [("2025-12".to_string(), 1), ("2026-01".to_string(), 3)]
+        );
+    }
+
+    #[test]
+    fn summarizes_validity_lengths() {
+        let validity = Stats::from_crts(&sample(), now()).validity.unwrap();
+
+        assert_eq!(validity.min, Duration::from_secs(30 * DAY));
+        assert_eq!(validity.max, Duration::from_secs(90 * DAY));
+        assert_eq!(validity.average, Duration::from_secs(45 * DAY));
+    }
+
+    #[test]
+    fn soonest_expiry_skips_expired_certificates() {
+        let expiry = Stats::from_crts(&sample(), now()).soonest_expiry.unwrap();
+
+        assert_eq!(expiry.id, 4);
+        assert_eq!(expiry.common_name, "api.example.com");
+        assert_eq!(expiry.not_after.raw(), "2026-02-19");
+    }
+
+    #[test]
+    fn empty_result_has_no_validity_or_expiry() {
+        let stats = Stats::from_crts(&[], now());
+
+        assert_eq!(stats.total, 0);
+        assert!(stats.issuer_organizations.is_empty());
+        assert!(stats.validity.is_none());
+        assert!(stats.soonest_expiry.is_none());
+    }
+}
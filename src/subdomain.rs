@@ -0,0 +1,131 @@
+use crate::crt::Crt;
+use crate::identity::IdentityKind;
+use std::collections::BTreeSet;
+
+// ワイルドカードの接頭辞
+const WILDCARD_PREFIX: &str = "*.";
+
+/// サブドメイン列挙のオプション
+#[derive(Debug, Clone, Default)]
+pub struct SubdomainOptions {
+    /// `*.` を取り除かずに残す
+    pub keep_wildcards: bool,
+    /// いずれかのドメイン配下の名前のみに絞り込む（空の場合は絞り込まない）
+    pub domains: Vec<String>,
+}
+
+/// 全証明書の DNS 名を正規化・重複排除・ソートして取得
+pub fn collect_subdomains(crts: &[Crt], options: &SubdomainOptions) -> Vec<String> {
+    let domains: Vec<String> = options
+        .domains
+        .iter()
+        .map(|domain| domain.trim_end_matches('.').to_ascii_lowercase())
+        .collect();
+
+    let names: BTreeSet<String> = crts
+        .iter()
+        .flat_map(Crt::identities)
+        .filter(|identity| identity.kind == IdentityKind::Dns)
+        .filter_map(|identity| {
+            let name = identity.value.trim_end_matches('.');
+            let bare = name.strip_prefix(WILDCARD_PREFIX).unwrap_or(name);
+
+            if bare.is_empty() || !is_in_scope(bare, &domains) {
+                return None;
+            }
+
+            Some(if options.keep_wildcards { name } else { bare }.to_string())
+        })
+        .collect();
+
+    names.into_iter().collect()
+}
+
+/// いずれかのドメイン配下（ドメイン自身を含む）の名前かどうか
+fn is_in_scope(name: &str, domains: &[String]) -> bool {
+    domains.is_empty()
+        || domains.iter().any(|domain| {
+            name == domain
+                || name
+                    .strip_suffix(domain.as_str())
+                    .is_some_and(|prefix| prefix.ends_with('.'))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn crt(name_value: &str) -> Crt {
+        serde_json::from_value(json!({
+            "id": 1,
+            "common_name": "example.com",
+            "entry_timestamp": "2025-12-16T21:50:43.225",
+            "issuer_ca_id": 1,
+            "issuer_name": "C=GB, O=Sectigo Limited",
+            "name_value": name_value,
+            "not_after": "2026-03-16T20:59:52",
+            "not_before": "2025-12-16T00:00:00",
+            "result_count": 1,
+            "serial_number": "00",
+        }))
+        .unwrap()
+    }
+
+    fn options(keep_wildcards: bool, domains: &[&str]) -> SubdomainOptions {
+        SubdomainOptions {
+            keep_wildcards,
+            domains: domains.iter().map(|domain| domain.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn collects_sorted_unique_dns_names() {
+        let crts = [
+            crt("WWW.example.com\nadmin@example.com\n192.0.2.1"),
+            crt("api.example.com\nwww.example.com."),
+        ];
+
+        assert_eq!(
+            collect_subdomains(&crts, &options(false, &[])),
+            ["api.example.com", "www.example.com"]
+        );
+    }
+
+    #[test]
+    fn strips_or_keeps_wildcard_prefix() {
+        let crts = [crt("*.example.com\nexample.com")];
+
+        assert_eq!(
+            collect_subdomains(&crts, &options(false, &[])),
+            ["example.com"]
+        );
+        assert_eq!(
+            collect_subdomains(&crts, &options(true, &[])),
+            ["*.example.com", "example.com"]
+        );
+    }
+
+    #[test]
+    fn scopes_to_domain_and_its_subdomains_only() {
+        let crts = [crt(
+            "example.com\na.example.com\nb.a.example.com\nnotexample.com\nexample.com.evil.test\nother.test",
+        )];
+
+        assert_eq!(
+            collect_subdomains(&crts, &options(false, &["Example.COM."])),
+            ["a.example.com", "b.a.example.com", "example.com"]
+        );
+    }
+
+    #[test]
+    fn scopes_to_any_of_several_domains() {
+        let crts = [crt("a.example.com\nb.example.org\nc.example.net")];
+
+        assert_eq!(
+            collect_subdomains(&crts, &options(false, &["example.com", "example.org"])),
+            ["a.example.com", "b.example.org"]
+        );
+    }
+}
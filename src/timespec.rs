@@ -0,0 +1,161 @@
+use crate::error::{Error, Result};
+use crate::timestamp::Timestamp;
+use chrono::{NaiveDateTime, TimeDelta};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+// 単位ごとの秒数
+const SECS_PER_MINUTE: u64 = 60;
+const SECS_PER_HOUR: u64 = 60 * SECS_PER_MINUTE;
+const SECS_PER_DAY: u64 = 24 * SECS_PER_HOUR;
+const SECS_PER_WEEK: u64 = 7 * SECS_PER_DAY;
+
+// エラーメッセージ
+const ERROR_INVALID_DURATION: &str =
+    "Invalid duration: '{}' (expected a number followed by s, m, h, d or w, e.g. 30d)";
+const ERROR_INVALID_TIME_SPEC: &str =
+    "Invalid date or duration: '{}' (expected e.g. 2025-01-01, 2025-01-01T09:00:00 or 7d)";
+
+/// 絶対日時または基準日時からさかのぼる期間
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimeSpec {
+    /// 指定した日時（UTC）
+    At(Timestamp),
+    /// 基準日時から指定期間だけ前
+    Ago(Duration),
+}
+
+impl TimeSpec {
+    /// `2025-01-01` のような日時、または `7d` のような期間を解析
+    pub fn parse(raw: &str) -> Result<Self> {
+        parse_duration(raw)
+            .map(TimeSpec::Ago)
+            .or_else(|_| Timestamp::parse(raw.trim()).map(TimeSpec::At))
+            .map_err(|_| Error::Config(ERROR_INVALID_TIME_SPEC.replace("{}", raw)))
+    }
+
+    /// 基準日時をもとに日時を確定
+    pub fn resolve(&self, now: NaiveDateTime) -> NaiveDateTime {
+        match self {
+            TimeSpec::At(timestamp) => timestamp.value(),
+            TimeSpec::Ago(duration) => TimeDelta::from_std(*duration)
+                .ok()
+                .and_then(|delta| now.checked_sub_signed(delta))
+                .unwrap_or(NaiveDateTime::MIN),
+        }
+    }
+}
+
+/// `30d` のような期間を解析（単位: s, m, h, d, w）
+pub fn parse_duration(raw: &str) -> Result<Duration> {
+    let value = raw.trim();
+    let invalid = || Error::Config(ERROR_INVALID_DURATION.replace("{}", raw));
+
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or_else(invalid)?;
+    let (amount, unit) = value.split_at(split);
+    let amount: u64 = amount.parse().map_err(|_| invalid())?;
+
+    let unit_secs = match unit {
+        "s" => 1,
+        "m" => SECS_PER_MINUTE,
+        "h" => SECS_PER_HOUR,
+        "d" => SECS_PER_DAY,
+        "w" => SECS_PER_WEEK,
+        _ => return Err(invalid()),
+    };
+
+    amount
+        .checked_mul(unit_secs)
+        .map(Duration::from_secs)
+        .ok_or_else(invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(raw: &str) -> NaiveDateTime {
+        Timestamp::parse(raw).unwrap().value()
+    }
+
+    #[test]
+    fn parses_durations_in_each_unit() {
+        assert_eq!(parse_duration("45s").unwrap(), Duration::from_secs(45));
+        assert_eq!(parse_duration("10m").unwrap(), Duration::from_secs(600));
+        assert_eq!(parse_duration("2h").unwrap(), Duration::from_secs(7_200));
+        assert_eq!(
+            parse_duration(" 30d ").unwrap(),
+            Duration::from_secs(2_592_000)
+        );
+        assert_eq!(parse_duration("1w").unwrap(), Duration::from_secs(604_800));
+        assert_eq!(parse_duration("0d").unwrap(), Duration::ZERO);
+    }
+
+    #[test]
+    fn rejects_malformed_durations() {
+        for raw in [
+            "",
+            "30",
+            "d",
+            "30x",
+            "30 d",
+            "-1d",
+            "1.5d",
+            "30D",
+            "18446744073709551615w",
+        ] {
+            assert!(
+                matches!(parse_duration(raw), Err(Error::Config(_))),
+                "{raw}"
+            );
+        }
+    }
+
+    #[test]
+    fn parses_relative_and_absolute_specs() {
+        assert_eq!(
+            TimeSpec::parse("7d").unwrap(),
+            TimeSpec::Ago(Duration::from_secs(7 * SECS_PER_DAY))
+        );
+
+        let TimeSpec::At(date) = TimeSpec::parse("2025-01-01").unwrap() else {
+            panic!("date must be absolute");
+        };
+        assert_eq!(date.value(), at("2025-01-01T00:00:00"));
+
+        let TimeSpec::At(datetime) = TimeSpec::parse("2025-01-01T09:30:00").unwrap() else {
+            panic!("datetime must be absolute");
+        };
+        assert_eq!(datetime.value(), at("2025-01-01T09:30:00"));
+    }
+
+    #[test]
+    fn rejects_values_that_are_neither_date_nor_duration() {
+        for raw in ["yesterday", "2025-13-01", "7days"] {
+            assert!(
+                matches!(TimeSpec::parse(raw), Err(Error::Config(_))),
+                "{raw}"
+            );
+        }
+    }
+
+    #[test]
+    fn resolves_relative_spec_against_reference_time() {
+        let now = at("2026-01-08T12:00:00");
+
+        assert_eq!(
+            TimeSpec::parse("7d").unwrap().resolve(now),
+            at("2026-01-01T12:00:00")
+        );
+        assert_eq!(
+            TimeSpec::parse("2025-06-01").unwrap().resolve(now),
+            at("2025-06-01")
+        );
+        assert_eq!(
+            TimeSpec::Ago(Duration::MAX).resolve(now),
+            NaiveDateTime::MIN
+        );
+    }
+}
@@ -0,0 +1,214 @@
+use crate::error::{Error, Result};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, SecondsFormat};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+// crt.sh の日時形式（小数秒の桁数は可変）
+const DATETIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f";
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+// エラーメッセージ
+const ERROR_INVALID_TIMESTAMP: &str = "Invalid timestamp: '{}'";
+
+/// crt.sh の日時（UTC）
+///
+/// 比較は解析後の日時で行い、シリアライズ時は元の文字列をそのまま出力する。
+#[derive(Debug, Clone)]
+pub struct Timestamp {
+    raw: String,
+    value: NaiveDateTime,
+}
+
+impl Timestamp {
+    /// crt.sh の日時文字列を解析
+    pub fn parse(raw: &str) -> Result<Self> {
+        let value = parse_datetime(raw)
+            .ok_or_else(|| Error::Config(ERROR_INVALID_TIMESTAMP.replace("{}", raw)))?;
+
+        Ok(Self {
+            raw: raw.to_string(),
+            value,
+        })
+    }
+
+    /// crt.sh から返された元の文字列
+    pub fn raw(&self) -> &str {
+        &self.raw
+    }
+
+    /// 解析済みの日時（UTC）
+    pub fn value(&self) -> NaiveDateTime {
+        self.value
+    }
+
+    /// RFC 3339 形式（UTC, 例: `2025-12-16T21:50:43.225Z`）
+    pub fn to_rfc3339(&self) -> String {
+        self.value
+            .and_utc()
+            .to_rfc3339_opts(SecondsFormat::AutoSi, true)
+    }
+}
+
+impl From<NaiveDateTime> for Timestamp {
+    fn from(value: NaiveDateTime) -> Self {
+        Self {
+            raw: value.format(DATETIME_FORMAT).to_string(),
+            value,
+        }
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.raw)
+    }
+}
+
+impl PartialEq for Timestamp {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl Eq for Timestamp {}
+
+impl PartialOrd for Timestamp {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Timestamp {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.value.cmp(&other.value)
+    }
+}
+
+impl Hash for Timestamp {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value.hash(state);
+    }
+}
+
+impl Serialize for Timestamp {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.raw)
+    }
+}
+
+impl<'de> Deserialize<'de> for Timestamp {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let raw = String::deserialize(deserializer)?;
+        let value = parse_datetime(&raw).ok_or_else(|| {
+            serde::de::Error::custom(ERROR_INVALID_TIMESTAMP.replace("{}", &raw))
+        })?;
+
+        Ok(Self { raw, value })
+    }
+}
+
+/// 日時文字列を解析（区切りの空白・末尾の Z・日付のみの形式も受け付ける）
+fn parse_datetime(raw: &str) -> Option<NaiveDateTime> {
+    let normalized = raw.trim().replacen(' ', "T", 1);
+    let normalized = normalized.trim_end_matches('Z');
+
+    NaiveDateTime::parse_from_str(normalized, DATETIME_FORMAT)
+        .or_else(|_| {
+            NaiveDate::parse_from_str(normalized, DATE_FORMAT)
+                .map(|date| date.and_time(NaiveTime::MIN))
+        })
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crt::Crt;
+    use serde_json::{Value, json};
+
+    fn datetime(raw: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(raw, DATETIME_FORMAT).unwrap()
+    }
+
+    #[test]
+    fn parses_mixed_fractional_seconds() {
+        let cases = [
+            ("2025-12-16T21:50:43", "2025-12-16T21:50:43"),
+            ("2025-12-16T21:50:43.2", "2025-12-16T21:50:43.200"),
+            ("2025-12-16T21:50:43.22", "2025-12-16T21:50:43.220"),
+            ("2025-12-16T21:50:43.225", "2025-12-16T21:50:43.225"),
+            ("2025-12-16T21:50:43.225123", "2025-12-16T21:50:43.225123"),
+        ];
+
+        for (raw, expected) in cases {
+            let timestamp = Timestamp::parse(raw).unwrap();
+            assert_eq!(timestamp.value(), datetime(expected), "{raw}");
+            assert_eq!(timestamp.raw(), raw);
+        }
+    }
+
+    #[test]
+    fn accepts_space_separator_utc_suffix_and_date_only() {
+        assert_eq!(
+            Timestamp::parse("2025-12-16 21:50:43.225").unwrap().value(),
+            datetime("2025-12-16T21:50:43.225")
+        );
+        assert_eq!(
+            Timestamp::parse("2025-12-16T21:50:43Z").unwrap().value(),
+            datetime("2025-12-16T21:50:43")
+        );
+        assert_eq!(
+            Timestamp::parse("2025-12-16").unwrap().value(),
+            datetime("2025-12-16T00:00:00")
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_timestamp() {
+        assert!(matches!(
+            Timestamp::parse("16/12/2025"),
+            Err(Error::Config(_))
+        ));
+    }
+
+    #[test]
+    fn compares_by_value_and_serializes_raw() {
+        let short = Timestamp::parse("2025-12-16T21:50:43.2").unwrap();
+        let long = Timestamp::parse("2025-12-16T21:50:43.200000").unwrap();
+
+        assert_eq!(short, long);
+        assert!(short < Timestamp::parse("2025-12-16T21:50:43.21").unwrap());
+        assert_eq!(
+            serde_json::to_string(&long).unwrap(),
+            r#""2025-12-16T21:50:43.200000""#
+        );
+        assert_eq!(short.to_rfc3339(), "2025-12-16T21:50:43.200Z");
+    }
+
+    #[test]
+    fn deserializes_crt_with_null_entry_timestamp() {
+        let value = json!({
+            "id": 1,
+            "common_name": "example.com",
+            "entry_timestamp": null,
+            "issuer_ca_id": -1,
+            "issuer_name": "C=GB, O=Sectigo Limited",
+            "name_value": "example.com",
+            "not_after": "2026-03-16T20:59:52",
+            "not_before": "2025-12-16T00:00:00",
+            "result_count": 1,
+            "serial_number": "00",
+        });
+
+        let crt = Crt::deserialize(&value).unwrap();
+
+        assert!(crt.entry_timestamp.is_none());
+        assert_eq!(crt.not_after.value(), datetime("2026-03-16T20:59:52"));
+        assert_eq!(
+            serde_json::to_value(&crt).unwrap()["entry_timestamp"],
+            Value::Null
+        );
+    }
+}
@@ -0,0 +1,134 @@
+use crate::client::{Config, ERROR_REQUEST_CLONE, Response, ResponseInfo, TimingInfo};
+use crate::error::{Error, Result};
+use crate::retry::{RetryHook, RetryState};
+use std::pin::pin;
+use std::task::{Context, Poll, Waker};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// HTTP の送受信と待機（同期・非同期クライアントで異なる部分のみ）
+pub(crate) trait Transport {
+    type Request;
+    type Response;
+
+    /// リトライ用にリクエストを複製（ボディがストリームの場合は None）
+    fn try_clone(request: &Self::Request) -> Option<Self::Request>;
+
+    /// リクエストを送信
+    fn execute(
+        &self,
+        request: Self::Request,
+    ) -> impl Future<Output = reqwest::Result<Self::Response>>;
+
+    /// ステータス・バージョン・ヘッダー
+    fn info(response: &Self::Response) -> ResponseInfo;
+
+    /// ボディを読み込む
+    fn text(response: Self::Response) -> impl Future<Output = reqwest::Result<String>>;
+
+    /// リトライまで待機
+    fn sleep(delay: Duration) -> impl Future<Output = ()>;
+}
+
+impl Transport for reqwest::blocking::Client {
+    type Request = reqwest::blocking::Request;
+    type Response = reqwest::blocking::Response;
+
+    fn try_clone(request: &Self::Request) -> Option<Self::Request> {
+        request.try_clone()
+    }
+
+    async fn execute(&self, request: Self::Request) -> reqwest::Result<Self::Response> {
+        reqwest::blocking::Client::execute(self, request)
+    }
+
+    fn info(response: &Self::Response) -> ResponseInfo {
+        ResponseInfo::new(
+            response.status(),
+            response.version(),
+            response.headers().clone(),
+        )
+    }
+
+    async fn text(response: Self::Response) -> reqwest::Result<String> {
+        response.text()
+    }
+
+    async fn sleep(delay: Duration) {
+        thread::sleep(delay);
+    }
+}
+
+/// リトライ機能付きでリクエストを実行（リトライの判定・通知・計時は同期・非同期クライアントで共通）
+pub(crate) async fn execute_with_retry<T: Transport>(
+    transport: &T,
+    request: T::Request,
+    config: &Config,
+    retry_hook: Option<&RetryHook>,
+) -> Result<Response> {
+    let mut retry_state = RetryState::new(config, retry_hook);
+
+    loop {
+        retry_state.begin_attempt();
+
+        let retry_request =
+            T::try_clone(&request).ok_or_else(|| Error::Config(ERROR_REQUEST_CLONE.to_string()))?;
+
+        match transport.execute(retry_request).await {
+            Ok(response) => {
+                let info = T::info(&response);
+                if let Some(delay) = retry_state.retry_for_status(info.status(), info.headers()) {
+                    T::sleep(delay).await;
+                    continue;
+                }
+
+                return handle_successful_response::<T>(response, info, &retry_state).await;
+            }
+            Err(e) => {
+                if let Some(delay) = retry_state.retry_for_error(&e) {
+                    T::sleep(delay).await;
+                    continue;
+                }
+
+                return Err(e.into());
+            }
+        }
+    }
+}
+
+/// 成功したレスポンスを処理
+async fn handle_successful_response<T: Transport>(
+    response: T::Response,
+    info: ResponseInfo,
+    retry_state: &RetryState<'_>,
+) -> Result<Response> {
+    let response_received_time = retry_state.request_elapsed();
+
+    let body_start = Instant::now();
+    let response_body = T::text(response).await?;
+    let body_read_time = body_start.elapsed();
+
+    let timing = TimingInfo::new(
+        response_received_time,
+        body_read_time,
+        retry_state.total_elapsed(),
+    );
+
+    Response::received(info, response_body, timing)
+}
+
+/// 同期クライアントのトランスポートで Future を最後まで実行
+///
+/// 同期のトランスポートは送信・待機をその場で行うため、ポーリングは 1 回で完了する。
+pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let mut context = Context::from_waker(Waker::noop());
+
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+
+        thread::yield_now();
+    }
+}
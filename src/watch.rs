@@ -0,0 +1,575 @@
+use crate::cache::{cache_key, fnv1a};
+use crate::client::CrtShClient;
+use crate::crt::Crt;
+use crate::error::{Error, Result};
+use crate::webhook::{Delivery, Webhook};
+use serde::{Deserialize, Serialize};
+use std::borrow::Borrow;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+// 状態ファイルの保存先
+const STATE_DIR_NAME: &str = "rs-crtsh";
+const WATCH_DIR_NAME: &str = "watch";
+const STATE_HOME_ENV: &str = "XDG_STATE_HOME";
+const HOME_ENV: &str = "HOME";
+const HOME_STATE_DIR: &str = ".local/state";
+const STATE_FILE_EXTENSION: &str = "json";
+const STATE_TEMP_EXTENSION: &str = "tmp";
+
+// 状態ファイルに残す確認済みの ID の上限（超えた分は検索結果にない古い ID から削除）
+const MAX_SEEN_IDS: usize = 100_000;
+
+// エラーメッセージ
+const ERROR_READ_STATE: &str = "Failed to read watch state '{1}': {2}";
+const ERROR_WRITE_STATE: &str = "Failed to write watch state '{1}': {2}";
+
+/// 監視のオプション
+#[derive(Debug, Clone)]
+pub struct WatchOptions {
+    /// 検索の間隔
+    pub interval: Duration,
+    /// 確認済みの証明書 ID を保存するファイル
+    pub state_path: PathBuf,
+    /// 1 回だけ検索して終了する
+    pub once: bool,
+    /// 新しい証明書を通知する Webhook
+    pub webhooks: Vec<Webhook>,
+    /// Webhook に送信せずにペイロードを表示する
+    pub dry_run: bool,
+    /// 現在の検索結果を出力・通知せずに確認済みとして記録し、1 回で終了する
+    pub baseline: bool,
+    /// 通知に表示する監視対象（検索語など）
+    pub label: String,
+}
+
+/// 1 回の監視の結果
+#[derive(Debug)]
+pub struct WatchReport<T> {
+    /// 前回までに確認していなかった証明書（検索結果の順、同じ ID は最初の 1 件のみ）
+    pub new: Vec<T>,
+    /// 確認済みの ID の数（今回記録した ID を含む）
+    pub seen: usize,
+    /// Webhook ごとの送信結果
+    pub deliveries: Vec<(Webhook, Delivery)>,
+}
+
+/// 前回までに確認していない証明書を検出し、Webhook に通知する監視
+pub struct Watcher {
+    client: CrtShClient,
+    options: WatchOptions,
+}
+
+impl Watcher {
+    /// Webhook の送信に使用するクライアントと監視のオプションから作成
+    pub fn new(client: CrtShClient, options: WatchOptions) -> Self {
+        Self { client, options }
+    }
+
+    pub fn options(&self) -> &WatchOptions {
+        &self.options
+    }
+
+    /// 検索結果から新しい証明書を検出して on_new に渡し、確認済みとして記録してから Webhook に通知
+    ///
+    /// on_new は新しい証明書がある場合のみ呼び出し、成功した時点で確認済みとする。Webhook への
+    /// 送信に失敗した場合は、その Webhook のみ未通知の ID を状態ファイルに残し、次回の実行で
+    /// 新しい証明書と合わせて再送する（再送時に検索結果に含まれない証明書は送信しない）。
+    /// baseline の場合は出力・通知せずに記録のみ行う。
+    pub fn check<T: Borrow<Crt>>(
+        &self,
+        crts: Vec<T>,
+        on_new: impl FnOnce(&[T]) -> Result<()>,
+    ) -> Result<WatchReport<T>> {
+        let mut state = WatchState::load(&self.options.state_path)?;
+        let retries: Vec<Vec<Crt>> = self
+            .options
+            .webhooks
+            .iter()
+            .map(|webhook| state.pending_crts(webhook, &crts))
+            .collect();
+        let current: HashSet<u64> = crts.iter().map(|crt| crt.borrow().id).collect();
+        let new = state.unseen(crts);
+
+        if !new.is_empty() && !self.options.baseline {
+            on_new(&new)?;
+        }
+
+        for crt in &new {
+            state.insert(crt.borrow().id);
+        }
+        state.evict(&current, MAX_SEEN_IDS);
+
+        let mut deliveries = Vec::new();
+        if !self.options.baseline {
+            let new_crts: Vec<Crt> = new.iter().map(|crt| crt.borrow().clone()).collect();
+
+            for (webhook, mut crts) in self.options.webhooks.iter().zip(retries) {
+                crts.extend(new_crts.iter().cloned());
+                if crts.is_empty() {
+                    state.clear_pending(webhook);
+                    continue;
+                }
+
+                let delivery =
+                    webhook.deliver(&self.client, &crts, &self.options.label, self.options.dry_run);
+                match &delivery {
+                    Delivery::Sent => state.clear_pending(webhook),
+                    Delivery::Failed(_) => state.set_pending(webhook, &crts),
+                    Delivery::DryRun(_) => {}
+                }
+                deliveries.push((webhook.clone(), delivery));
+            }
+
+            state.retain_pending(&self.options.webhooks);
+        }
+
+        state.save(&self.options.state_path)?;
+
+        Ok(WatchReport {
+            new,
+            seen: state.len(),
+            deliveries,
+        })
+    }
+
+    /// interval ごとに cycle を繰り返す（once・baseline の場合は 1 回だけ実行して結果を返す）
+    ///
+    /// 繰り返し中のエラーは on_error に渡して監視を続ける。
+    pub fn run(
+        &self,
+        mut cycle: impl FnMut(&Self) -> Result<()>,
+        mut on_error: impl FnMut(Error),
+    ) -> Result<()> {
+        loop {
+            let result = cycle(self);
+            if self.options.once || self.options.baseline {
+                return result;
+            }
+
+            if let Err(e) = result {
+                on_error(e);
+            }
+
+            thread::sleep(self.options.interval);
+        }
+    }
+}
+
+/// 監視で確認済みの証明書 ID の一覧（最大 MAX_SEEN_IDS 件、検索結果にない古い ID から削除）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WatchState {
+    seen: BTreeSet<u64>,
+    /// 送信に失敗した Webhook ごとの未通知の ID（キーは Webhook の URL のハッシュ）
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pending: BTreeMap<String, BTreeSet<u64>>,
+}
+
+impl WatchState {
+    /// 状態ファイルを読み込む（ファイルがない場合は空の状態）
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(state_error(ERROR_READ_STATE, path, e)),
+        };
+
+        serde_json::from_str(&contents).map_err(|e| state_error(ERROR_READ_STATE, path, e))
+    }
+
+    /// 状態ファイルに保存
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let contents =
+            serde_json::to_string(self).map_err(|e| state_error(ERROR_WRITE_STATE, path, e))?;
+        let temp_path = path.with_extension(STATE_TEMP_EXTENSION);
+
+        path.parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(&temp_path, contents))
+            .and_then(|_| fs::rename(&temp_path, path))
+            .map_err(|e| state_error(ERROR_WRITE_STATE, path, e))
+    }
+
+    /// 確認済みとして記録（初めて確認した ID の場合は true）
+    pub fn insert(&mut self, id: u64) -> bool {
+        self.seen.insert(id)
+    }
+
+    /// 確認済みかどうか
+    pub fn contains(&self, id: u64) -> bool {
+        self.seen.contains(&id)
+    }
+
+    /// 確認済みの ID が max を超えた分を、current（今回の検索結果）にない古い ID から削除
+    ///
+    /// crt.sh の ID は登録順に増えるため、小さい ID から削除する。current の ID は上限を超えても残す。
+    fn evict(&mut self, current: &HashSet<u64>, max: usize) {
+        let mut excess = self.seen.len().saturating_sub(max);
+
+        self.seen.retain(|id| {
+            if excess > 0 && !current.contains(id) {
+                excess -= 1;
+                false
+            } else {
+                true
+            }
+        });
+    }
+
+    /// 確認していない証明書を抽出（同じ ID は最初の 1 件のみ、状態は変更しない）
+    pub fn unseen<T: Borrow<Crt>>(&self, crts: Vec<T>) -> Vec<T> {
+        let mut found = HashSet::new();
+
+        crts.into_iter()
+            .filter(|crt| {
+                let id = crt.borrow().id;
+                !self.contains(id) && found.insert(id)
+            })
+            .collect()
+    }
+
+    /// Webhook に未通知の証明書を検索結果から抽出（同じ ID は最初の 1 件のみ）
+    fn pending_crts<T: Borrow<Crt>>(&self, webhook: &Webhook, crts: &[T]) -> Vec<Crt> {
+        let Some(pending) = self.pending.get(&webhook_key(webhook)) else {
+            return Vec::new();
+        };
+        let mut found = HashSet::new();
+
+        crts.iter()
+            .map(Borrow::borrow)
+            .filter(|crt| pending.contains(&crt.id) && found.insert(crt.id))
+            .cloned()
+            .collect()
+    }
+
+    /// Webhook に未通知の証明書を記録
+    fn set_pending(&mut self, webhook: &Webhook, crts: &[Crt]) {
+        self.pending
+            .insert(webhook_key(webhook), crts.iter().map(|crt| crt.id).collect());
+    }
+
+    /// Webhook に未通知の証明書の記録を削除
+    fn clear_pending(&mut self, webhook: &Webhook) {
+        self.pending.remove(&webhook_key(webhook));
+    }
+
+    /// 設定から削除された Webhook の未通知の記録を削除
+    fn retain_pending(&mut self, webhooks: &[Webhook]) {
+        let keys: HashSet<String> = webhooks.iter().map(webhook_key).collect();
+        self.pending.retain(|key, _| keys.contains(key));
+    }
+
+    /// 確認済みの ID の数
+    pub fn len(&self) -> usize {
+        self.seen.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.seen.is_empty()
+    }
+}
+
+/// 検索内容ごとのデフォルトの状態ファイル
+/// （$XDG_STATE_HOME/rs-crtsh/watch または ~/.local/state/rs-crtsh/watch 配下）
+///
+/// targets は検索する URL（一括検索の場合は全 URL、保存済みの結果を監視する場合は入力ファイル）。
+pub fn default_state_path(targets: &[String]) -> Option<PathBuf> {
+    let state_home = env::var_os(STATE_HOME_ENV)
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os(HOME_ENV).map(|home| PathBuf::from(home).join(HOME_STATE_DIR)))?;
+
+    let key = targets
+        .iter()
+        .map(|target| cache_key(target))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    Some(
+        state_home
+            .join(STATE_DIR_NAME)
+            .join(WATCH_DIR_NAME)
+            .join(format!("{:016x}", fnv1a(key.as_bytes())))
+            .with_extension(STATE_FILE_EXTENSION),
+    )
+}
+
+/// 状態ファイルで Webhook を識別するキー（URL のトークンを保存しないようハッシュ化）
+fn webhook_key(webhook: &Webhook) -> String {
+    format!("{:016x}", fnv1a(webhook.url.as_bytes()))
+}
+
+/// 状態ファイルの読み書きエラーを作成
+fn state_error(template: &str, path: &Path, error: impl std::fmt::Display) -> Error {
+    Error::Config(
+        template
+            .replace("{1}", &path.display().to_string())
+            .replace("{2}", &error.to_string()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::Config;
+    use crate::webhook::WebhookKind;
+    use serde_json::{Value, json};
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+
+    fn crt(id: u64) -> Crt {
+        serde_json::from_value(json!({
+            "id": id,
+            "common_name": "example.com",
+            "entry_timestamp": "2025-12-16T21:50:43.225",
+            "issuer_ca_id": 1,
+            "issuer_name": "C=GB, O=Sectigo Limited",
+            "name_value": "example.com",
+            "not_after": "2026-03-16T20:59:52",
+            "not_before": "2025-12-16T00:00:00",
+            "result_count": 1,
+            "serial_number": "00",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn unseen_skips_seen_and_duplicate_ids() {
+        let mut state = WatchState::default();
+        state.insert(1);
+
+        let unseen = state.unseen(vec![crt(1), crt(2), crt(3), crt(2)]);
+
+        assert_eq!(unseen.iter().map(|crt| crt.id).collect::<Vec<_>>(), [2, 3]);
+        assert_eq!(state.len(), 1);
+    }
+
+    #[test]
+    fn missing_state_file_is_empty() {
+        let path = env::temp_dir().join("rs-crtsh-watch-missing-state.json");
+
+        assert!(WatchState::load(&path).unwrap().is_empty());
+    }
+
+    /// status_line を返し、受け取った generic ペイロードの証明書 ID を返すローカルの Webhook
+    fn serve(
+        status_line: &'static str,
+        requests: usize,
+    ) -> (Webhook, thread::JoinHandle<Vec<Vec<u64>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let webhook = Webhook {
+            kind: WebhookKind::Generic,
+            url: format!("http://{}/hook", listener.local_addr().unwrap()),
+            template: None,
+        };
+
+        let server = thread::spawn(move || {
+            listener
+                .incoming()
+                .take(requests)
+                .map(|stream| {
+                    let mut reader = BufReader::new(stream.unwrap());
+                    let mut length = 0;
+                    loop {
+                        let mut line = String::new();
+                        reader.read_line(&mut line).unwrap();
+                        if line == "\r\n" {
+                            break;
+                        }
+                        if let Some((name, value)) = line.split_once(':')
+                            && name.eq_ignore_ascii_case("content-length")
+                        {
+                            length = value.trim().parse().unwrap();
+                        }
+                    }
+                    let mut body = vec![0; length];
+                    reader.read_exact(&mut body).unwrap();
+                    reader
+                        .get_mut()
+                        .write_all(
+                            format!(
+                                "{}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                                status_line
+                            )
+                            .as_bytes(),
+                        )
+                        .unwrap();
+
+                    let payload: Value = serde_json::from_slice(&body).unwrap();
+                    payload["certificates"]
+                        .as_array()
+                        .unwrap()
+                        .iter()
+                        .map(|crt| crt["id"].as_u64().unwrap())
+                        .collect()
+                })
+                .collect()
+        });
+
+        (webhook, server)
+    }
+
+    fn webhook() -> Webhook {
+        Webhook {
+            kind: WebhookKind::Generic,
+            url: "http://127.0.0.1/hook".to_string(),
+            template: None,
+        }
+    }
+
+    fn watcher(name: &str, webhooks: Vec<Webhook>) -> Watcher {
+        let state_path = env::temp_dir().join(format!(
+            "rs-crtsh-watch-{}-{}.json",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_file(&state_path);
+
+        Watcher::new(
+            CrtShClient::new(Config::default()).unwrap(),
+            WatchOptions {
+                interval: Duration::ZERO,
+                state_path,
+                once: true,
+                webhooks,
+                dry_run: false,
+                baseline: false,
+                label: "example.com".to_string(),
+            },
+        )
+    }
+
+    #[test]
+    fn check_retries_only_failed_webhook() {
+        let (ok, ok_server) = serve("HTTP/1.1 200 OK", 2);
+        let (down, down_server) = serve("HTTP/1.1 500 Internal Server Error", 2);
+        let watcher = watcher("retry", vec![ok.clone(), down.clone()]);
+
+        let report = watcher.check(vec![crt(1), crt(2)], |_| Ok(())).unwrap();
+        assert!(matches!(
+            report.deliveries[..],
+            [(_, Delivery::Sent), (_, Delivery::Failed(_))]
+        ));
+
+        let state = WatchState::load(&watcher.options().state_path).unwrap();
+        assert_eq!(state.len(), 2);
+        assert!(!state.pending.contains_key(&webhook_key(&ok)));
+        assert_eq!(state.pending[&webhook_key(&down)], BTreeSet::from([1, 2]));
+
+        let report = watcher
+            .check(vec![crt(1), crt(2), crt(3)], |_| Ok(()))
+            .unwrap();
+        assert_eq!(report.new.iter().map(|crt| crt.id).collect::<Vec<_>>(), [3]);
+
+        assert_eq!(ok_server.join().unwrap(), [vec![1, 2], vec![3]]);
+        assert_eq!(down_server.join().unwrap(), [vec![1, 2], vec![1, 2, 3]]);
+
+        let state = WatchState::load(&watcher.options().state_path).unwrap();
+        assert_eq!(
+            state.pending[&webhook_key(&down)],
+            BTreeSet::from([1, 2, 3])
+        );
+        fs::remove_file(&watcher.options().state_path).unwrap();
+    }
+
+    #[test]
+    fn check_records_nothing_when_on_new_fails() {
+        let watcher = watcher("on-new", Vec::new());
+
+        let result = watcher.check(vec![crt(1)], |_| Err(Error::Output("closed".to_string())));
+
+        assert!(result.is_err());
+        assert!(
+            WatchState::load(&watcher.options().state_path)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn evict_removes_oldest_ids_not_in_current_results() {
+        let mut state = WatchState::default();
+        for id in 1..=6 {
+            state.insert(id);
+        }
+
+        state.evict(&HashSet::from([1, 2]), 3);
+
+        assert_eq!(state.seen, BTreeSet::from([1, 2, 6]));
+    }
+
+    #[test]
+    fn evict_keeps_current_results_over_limit() {
+        let mut state = WatchState::default();
+        for id in 1..=4 {
+            state.insert(id);
+        }
+
+        state.evict(&HashSet::from([1, 2, 3, 4]), 2);
+
+        assert_eq!(state.len(), 4);
+    }
+
+    #[test]
+    fn state_round_trips_through_file() {
+        let path = env::temp_dir().join(format!(
+            "rs-crtsh-watch-round-trip-{}.json",
+            std::process::id()
+        ));
+        let mut state = WatchState::default();
+        state.insert(3);
+        state.insert(1);
+        state.set_pending(&webhook(), &[crt(3)]);
+
+        state.save(&path).unwrap();
+        let loaded = WatchState::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.seen, state.seen);
+        assert_eq!(loaded.pending, state.pending);
+        assert!(!path.with_extension(STATE_TEMP_EXTENSION).exists());
+    }
+
+    #[test]
+    fn check_reports_only_new_certificates() {
+        let watcher = watcher("new", Vec::new());
+
+        let first = watcher.check(vec![crt(1), crt(2)], |_| Ok(())).unwrap();
+        let second = watcher
+            .check(vec![crt(2), crt(3), crt(3)], |new| {
+                assert_eq!(new.iter().map(|crt| crt.id).collect::<Vec<_>>(), [3]);
+                Ok(())
+            })
+            .unwrap();
+        let third = watcher
+            .check(vec![crt(3)], |_| panic!("no new certificates"))
+            .unwrap();
+        fs::remove_file(&watcher.options().state_path).unwrap();
+
+        assert_eq!((first.new.len(), first.seen), (2, 2));
+        assert_eq!((second.new.len(), second.seen), (1, 3));
+        assert_eq!((third.new.len(), third.seen), (0, 3));
+    }
+
+    #[test]
+    fn baseline_records_without_output_or_delivery() {
+        let mut watcher = watcher("baseline", vec![webhook()]);
+        watcher.options.baseline = true;
+
+        let report = watcher
+            .check(vec![crt(1), crt(2)], |_| panic!("baseline must not output"))
+            .unwrap();
+        let state = WatchState::load(&watcher.options().state_path).unwrap();
+        fs::remove_file(&watcher.options().state_path).unwrap();
+
+        assert_eq!(report.new.len(), 2);
+        assert!(report.deliveries.is_empty());
+        assert_eq!(state.seen, BTreeSet::from([1, 2]));
+        assert!(state.pending.is_empty());
+    }
+}
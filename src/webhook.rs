@@ -0,0 +1,581 @@
+use crate::client::CrtShClient;
+use crate::crt::Crt;
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use reqwest::Url;
+use serde_json::{Value, json};
+use std::str::FromStr;
+
+// 1 つのメッセージに列挙する証明書の最大数
+const MAX_LISTED_CERTIFICATES: usize = 20;
+
+// 種類と URL の区切り文字（`slack=https://...`）
+const KIND_SEPARATOR: char = '=';
+
+// メッセージ
+const MESSAGE_TITLE: &str = "rs-crtsh: {1} new certificates for {2}";
+const MESSAGE_MORE: &str = "...and {} more";
+const CRT_SH_CERTIFICATE_URL: &str = "https://crt.sh/?id={}";
+const SLACK_LINE: &str = "• <{1}|{2}> ({3}, expires {4})";
+const TEAMS_LINE: &str = "- [{2}]({1}) ({3}, expires {4})";
+
+// Teams の MessageCard
+const TEAMS_CARD_TYPE: &str = "MessageCard";
+const TEAMS_CARD_CONTEXT: &str = "https://schema.org/extensions";
+
+// テンプレートのプレースホルダー
+const PLACEHOLDER_COUNT: &str = "{count}";
+const PLACEHOLDER_QUERY: &str = "{query}";
+const PLACEHOLDER_IDS: &str = "{ids}";
+const PLACEHOLDER_NAMES: &str = "{names}";
+const PLACEHOLDER_CERTIFICATES: &str = "{certificates}";
+
+// 表示用に URL のパスとクエリを隠す
+const REDACTED_PATH: &str = "/***";
+const REDACTED_URL: &str = "***";
+
+// エラーメッセージ
+const ERROR_UNKNOWN_KIND: &str = "Unknown webhook kind: '{}' (expected generic, slack or teams)";
+const ERROR_EMPTY_URL: &str = "Webhook URL is empty";
+
+/// Webhook のペイロードの形式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookKind {
+    /// 任意の JSON（テンプレートで本文を指定可能）
+    #[default]
+    Generic,
+    /// Slack の Incoming Webhook
+    Slack,
+    /// Microsoft Teams の Incoming Webhook（MessageCard）
+    Teams,
+}
+
+/// 新しい証明書の通知先
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Webhook {
+    #[serde(default)]
+    pub kind: WebhookKind,
+    pub url: String,
+    /// generic では本文全体、slack・teams ではメッセージ本文のテンプレート
+    #[serde(default)]
+    pub template: Option<String>,
+}
+
+/// Webhook への送信結果
+#[derive(Debug)]
+pub enum Delivery {
+    /// 送信に成功
+    Sent,
+    /// dry run のため送信していない（送信するはずだったペイロード）
+    DryRun(String),
+    /// 送信に失敗（リトライ設定に従って再送した後のエラー）
+    Failed(Error),
+}
+
+impl WebhookKind {
+    /// 形式名
+    pub fn name(self) -> &'static str {
+        match self {
+            WebhookKind::Generic => "generic",
+            WebhookKind::Slack => "slack",
+            WebhookKind::Teams => "teams",
+        }
+    }
+}
+
+impl FromStr for WebhookKind {
+    type Err = Error;
+
+    fn from_str(name: &str) -> Result<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "generic" => Ok(WebhookKind::Generic),
+            "slack" => Ok(WebhookKind::Slack),
+            "teams" => Ok(WebhookKind::Teams),
+            _ => Err(Error::Config(ERROR_UNKNOWN_KIND.replace("{}", name))),
+        }
+    }
+}
+
+impl Webhook {
+    /// `[kind=]url` 形式の指定を解析（種類の省略時は generic）
+    pub fn parse(raw: &str) -> Result<Self> {
+        let raw = raw.trim();
+        let (kind, url) = match raw.split_once(KIND_SEPARATOR) {
+            Some((kind, url)) if !kind.contains(':') && !kind.contains('/') => {
+                (kind.parse()?, url.trim())
+            }
+            _ => (WebhookKind::Generic, raw),
+        };
+
+        if url.is_empty() {
+            return Err(Error::Config(ERROR_EMPTY_URL.to_string()));
+        }
+
+        Ok(Self {
+            kind,
+            url: url.to_string(),
+            template: None,
+        })
+    }
+
+    /// 表示用の URL（URL に含まれるトークンを出力しないよう、スキームとホストのみ）
+    pub fn redacted_url(&self) -> String {
+        match Url::parse(&self.url) {
+            Ok(url) if url.has_host() => url.origin().ascii_serialization() + REDACTED_PATH,
+            _ => REDACTED_URL.to_string(),
+        }
+    }
+
+    /// 新しい証明書を 1 つのメッセージにまとめて送信（dry run の場合はペイロードを返す）
+    ///
+    /// 送信エラーには URL を含めない。
+    pub fn deliver(
+        &self,
+        client: &CrtShClient,
+        crts: &[Crt],
+        query: &str,
+        dry_run: bool,
+    ) -> Delivery {
+        let payload = self.payload(crts, query);
+        if dry_run {
+            return Delivery::DryRun(payload);
+        }
+
+        match client.post_json(&self.url, payload) {
+            Ok(_) => Delivery::Sent,
+            Err(Error::Timeout(e)) => Delivery::Failed(Error::Timeout(e.without_url())),
+            Err(Error::Request(e)) => Delivery::Failed(Error::Request(e.without_url())),
+            Err(e) => Delivery::Failed(e),
+        }
+    }
+
+    /// 新しい証明書をまとめた 1 つのメッセージのペイロード（JSON 文字列）
+    pub fn payload(&self, crts: &[Crt], query: &str) -> String {
+        match self.kind {
+            WebhookKind::Generic => match &self.template {
+                Some(template) => render_template(template, crts, query, true),
+                None => json!({
+                    "query": query,
+                    "count": crts.len(),
+                    "certificates": crts,
+                })
+                .to_string(),
+            },
+            WebhookKind::Slack => json!({
+                "text": self.message(crts, query, SLACK_LINE, "\n"),
+            })
+            .to_string(),
+            WebhookKind::Teams => json!({
+                "@type": TEAMS_CARD_TYPE,
+                "@context": TEAMS_CARD_CONTEXT,
+                "summary": title(crts, query),
+                "title": title(crts, query),
+                "text": self.message(crts, query, TEAMS_LINE, "\n\n"),
+            })
+            .to_string(),
+        }
+    }
+
+    /// slack・teams のメッセージ本文（テンプレートがない場合は証明書の一覧）
+    fn message(&self, crts: &[Crt], query: &str, line_template: &str, separator: &str) -> String {
+        if let Some(template) = &self.template {
+            return render_template(template, crts, query, false);
+        }
+
+        let mut lines = vec![title(crts, query)];
+        lines.extend(crts.iter().take(MAX_LISTED_CERTIFICATES).map(|crt| {
+            let issuer = crt.issuer();
+            replace_placeholders(
+                line_template,
+                &[
+                    ("{1}", &CRT_SH_CERTIFICATE_URL.replace("{}", &crt.id.to_string())),
+                    ("{2}", &crt.common_name),
+                    ("{3}", issuer.organization().unwrap_or(&crt.issuer_name)),
+                    ("{4}", crt.not_after.raw()),
+                ],
+            )
+        }));
+
+        if crts.len() > MAX_LISTED_CERTIFICATES {
+            lines.push(MESSAGE_MORE.replace("{}", &(crts.len() - MAX_LISTED_CERTIFICATES).to_string()));
+        }
+
+        lines.join(separator)
+    }
+}
+
+/// 新しい証明書を 1 つのメッセージにまとめて各 Webhook に送信（失敗しても残りの送信は続行）
+pub fn notify_webhooks(
+    client: &CrtShClient,
+    webhooks: &[Webhook],
+    crts: &[Crt],
+    query: &str,
+    dry_run: bool,
+) -> Vec<(Webhook, Delivery)> {
+    webhooks
+        .iter()
+        .map(|webhook| (webhook.clone(), webhook.deliver(client, crts, query, dry_run)))
+        .collect()
+}
+
+/// メッセージの見出し
+fn title(crts: &[Crt], query: &str) -> String {
+    MESSAGE_TITLE
+        .replace("{1}", &crts.len().to_string())
+        .replace("{2}", query)
+}
+
+/// テンプレートのプレースホルダーを置換（escape 指定時は文字列を JSON 文字列の中身としてエスケープ）
+fn render_template(template: &str, crts: &[Crt], query: &str, escape: bool) -> String {
+    let text = |value: String| {
+        if escape {
+            let quoted = Value::String(value).to_string();
+            quoted[1..quoted.len() - 1].to_string()
+        } else {
+            value
+        }
+    };
+
+    let ids: Vec<String> = crts.iter().map(|crt| crt.id.to_string()).collect();
+    let names: Vec<&str> = crts.iter().map(|crt| crt.common_name.as_str()).collect();
+
+    replace_placeholders(
+        template,
+        &[
+            (PLACEHOLDER_COUNT, &crts.len().to_string()),
+            (PLACEHOLDER_QUERY, &text(query.to_string())),
+            (PLACEHOLDER_IDS, &text(ids.join(", "))),
+            (PLACEHOLDER_NAMES, &text(names.join(", "))),
+            (
+                PLACEHOLDER_CERTIFICATES,
+                &serde_json::to_string(crts).unwrap_or_default(),
+            ),
+        ],
+    )
+}
+
+/// テンプレートを先頭から 1 回だけ走査してプレースホルダーを置換
+///
+/// 置換した値（証明書の名前など）に含まれるプレースホルダーは展開しない。
+fn replace_placeholders(template: &str, values: &[(&str, &str)]) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(c) = rest.chars().next() {
+        match values.iter().find(|(placeholder, _)| rest.starts_with(placeholder)) {
+            Some((placeholder, value)) => {
+                rendered.push_str(value);
+                rest = &rest[placeholder.len()..];
+            }
+            None => {
+                rendered.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+
+    rendered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::Config;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    fn crt(id: u64, common_name: &str) -> Crt {
+        serde_json::from_value(json!({
+            "id": id,
+            "common_name": common_name,
+            "entry_timestamp": "2025-12-16T21:50:43.225",
+            "issuer_ca_id": 1,
+            "issuer_name": "C=GB, O=Sectigo Limited, CN=Sectigo CA",
+            "name_value": common_name,
+            "not_after": "2026-03-16T20:59:52",
+            "not_before": "2025-12-16T00:00:00",
+            "result_count": 1,
+            "serial_number": "00",
+        }))
+        .unwrap()
+    }
+
+    fn webhook(kind: WebhookKind, template: Option<&str>) -> Webhook {
+        Webhook {
+            kind,
+            url: "http://127.0.0.1/hook".to_string(),
+            template: template.map(String::from),
+        }
+    }
+
+    fn payload(webhook: &Webhook, crts: &[Crt]) -> Value {
+        serde_json::from_str(&webhook.payload(crts, "example.com")).unwrap()
+    }
+
+    #[test]
+    fn generic_payload_contains_certificates() {
+        let value = payload(
+            &webhook(WebhookKind::Generic, None),
+            &[crt(1, "a.example.com")],
+        );
+
+        assert_eq!(value["query"], "example.com");
+        assert_eq!(value["count"], 1);
+        assert_eq!(value["certificates"][0]["id"], 1);
+        assert_eq!(value["certificates"][0]["not_after"], "2026-03-16T20:59:52");
+    }
+
+    #[test]
+    fn slack_payload_lists_certificates() {
+        let value = payload(
+            &webhook(WebhookKind::Slack, None),
+            &[crt(1, "a.example.com")],
+        );
+
+        assert_eq!(
+            value["text"],
+            "rs-crtsh: 1 new certificates for example.com\n\
+             • <https://crt.sh/?id=1|a.example.com> (Sectigo Limited, expires 2026-03-16T20:59:52)"
+        );
+    }
+
+    #[test]
+    fn teams_payload_is_message_card() {
+        let value = payload(
+            &webhook(WebhookKind::Teams, None),
+            &[crt(1, "a.example.com")],
+        );
+
+        assert_eq!(value["@type"], TEAMS_CARD_TYPE);
+        assert_eq!(value["@context"], TEAMS_CARD_CONTEXT);
+        assert_eq!(
+            value["title"],
+            "rs-crtsh: 1 new certificates for example.com"
+        );
+        assert_eq!(
+            value["text"],
+            "rs-crtsh: 1 new certificates for example.com\n\n\
+             - [a.example.com](https://crt.sh/?id=1) (Sectigo Limited, expires 2026-03-16T20:59:52)"
+        );
+    }
+
+    #[test]
+    fn message_truncates_long_lists() {
+        let crts: Vec<Crt> = (0..MAX_LISTED_CERTIFICATES as u64 + 3)
+            .map(|id| crt(id, "a.example.com"))
+            .collect();
+        let value = payload(&webhook(WebhookKind::Slack, None), &crts);
+        let text = value["text"].as_str().unwrap();
+
+        assert_eq!(text.lines().count(), MAX_LISTED_CERTIFICATES + 2);
+        assert!(text.ends_with("...and 3 more"));
+    }
+
+    #[test]
+    fn generic_template_escapes_text_values() {
+        let webhook = webhook(
+            WebhookKind::Generic,
+            Some(
+                r#"{"names": "{names}", "ids": "{ids}", "count": {count}, "list": {certificates}}"#,
+            ),
+        );
+        let value = payload(
+            &webhook,
+            &[crt(1, r#"a"b\c.example.com"#), crt(2, "b.example.com")],
+        );
+
+        assert_eq!(value["names"], r#"a"b\c.example.com, b.example.com"#);
+        assert_eq!(value["ids"], "1, 2");
+        assert_eq!(value["count"], 2);
+        assert_eq!(value["list"][1]["id"], 2);
+    }
+
+    #[test]
+    fn render_template_without_escape_keeps_text() {
+        let rendered = render_template(
+            "{count} for {query}: {names}",
+            &[crt(1, r#"a"b.example.com"#)],
+            "example.com",
+            false,
+        );
+
+        assert_eq!(rendered, r#"1 for example.com: a"b.example.com"#);
+    }
+
+    #[test]
+    fn render_template_does_not_expand_placeholders_in_values() {
+        let rendered = render_template(
+            "{names} for {query}",
+            &[crt(1, "{query}.example.com")],
+            "{ids}",
+            false,
+        );
+
+        assert_eq!(rendered, "{query}.example.com for {ids}");
+    }
+
+    #[test]
+    fn message_lines_do_not_expand_placeholders_in_names() {
+        let value = payload(
+            &webhook(WebhookKind::Slack, None),
+            &[crt(1, "{3}.example.com")],
+        );
+
+        assert!(
+            value["text"]
+                .as_str()
+                .unwrap()
+                .contains("|{3}.example.com> (Sectigo Limited,")
+        );
+    }
+
+    #[test]
+    fn redacted_url_hides_path_and_query() {
+        let webhook =
+            Webhook::parse("slack=https://hooks.slack.com/services/T/B/X?token=1").unwrap();
+
+        assert_eq!(webhook.redacted_url(), "https://hooks.slack.com/***");
+        assert_eq!(Webhook::parse("not a url").unwrap().redacted_url(), "***");
+    }
+
+    #[test]
+    fn slack_template_replaces_message_text() {
+        let webhook = webhook(WebhookKind::Slack, Some(r#"New: {names} "{query}""#));
+        let value = payload(&webhook, &[crt(1, "a.example.com")]);
+
+        assert_eq!(value["text"], r#"New: a.example.com "example.com""#);
+    }
+
+    #[test]
+    fn parse_reads_kind_prefix() {
+        let webhook = Webhook::parse("slack=https://hooks.slack.com/services/T/B/X").unwrap();
+
+        assert_eq!(webhook.kind, WebhookKind::Slack);
+        assert_eq!(webhook.url, "https://hooks.slack.com/services/T/B/X");
+    }
+
+    #[test]
+    fn parse_keeps_query_string_of_generic_url() {
+        let webhook = Webhook::parse("https://example.com/hook?a=b&c=d").unwrap();
+
+        assert_eq!(webhook.kind, WebhookKind::Generic);
+        assert_eq!(webhook.url, "https://example.com/hook?a=b&c=d");
+    }
+
+    #[test]
+    fn parse_rejects_unknown_kind_and_empty_url() {
+        assert!(matches!(
+            Webhook::parse("discord=https://example.com"),
+            Err(Error::Config(_))
+        ));
+        assert!(matches!(Webhook::parse("teams="), Err(Error::Config(_))));
+    }
+
+    /// 1 件のリクエストを受け付けて status_line で応答するサーバー（リクエストのヘッダーとボディを返す）
+    fn serve_once(status_line: &'static str) -> (String, thread::JoinHandle<(String, String)>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+
+            let mut head = String::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line == "\r\n" {
+                    break;
+                }
+                head.push_str(&line);
+            }
+
+            let length: usize = head
+                .lines()
+                .find_map(|line| {
+                    let (name, value) = line.split_once(':')?;
+                    name.eq_ignore_ascii_case("content-length")
+                        .then(|| value.trim().parse().ok())?
+                })
+                .unwrap();
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+
+            let response =
+                format!("{status_line}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+            reader.get_mut().write_all(response.as_bytes()).unwrap();
+
+            (head, String::from_utf8(body).unwrap())
+        });
+
+        (url, server)
+    }
+
+    #[test]
+    fn notify_webhooks_posts_json_payload() {
+        let (url, server) = serve_once("HTTP/1.1 200 OK");
+
+        let client = CrtShClient::new(Config::default()).unwrap();
+        let webhooks = [Webhook::parse(&url).unwrap()];
+        let deliveries = notify_webhooks(
+            &client,
+            &webhooks,
+            &[crt(1, "a.example.com")],
+            "example.com",
+            false,
+        );
+
+        assert!(matches!(deliveries[..], [(_, Delivery::Sent)]));
+
+        let (head, body) = server.join().unwrap();
+        assert!(head.starts_with("POST /hook HTTP/1.1\r\n"));
+        assert!(
+            head.to_ascii_lowercase()
+                .contains("content-type: application/json")
+        );
+
+        let value: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(value["query"], "example.com");
+        assert_eq!(value["certificates"][0]["id"], 1);
+    }
+
+    #[test]
+    fn notify_webhooks_reports_failed_delivery() {
+        let (url, server) = serve_once("HTTP/1.1 500 Internal Server Error");
+
+        let client = CrtShClient::new(Config::default()).unwrap();
+        let webhooks = [Webhook::parse(&format!("slack={url}")).unwrap()];
+        let deliveries = notify_webhooks(
+            &client,
+            &webhooks,
+            &[crt(1, "a.example.com")],
+            "example.com",
+            false,
+        );
+        server.join().unwrap();
+
+        assert!(matches!(
+            deliveries[..],
+            [(_, Delivery::Failed(Error::HttpStatus { .. }))]
+        ));
+    }
+
+    #[test]
+    fn notify_webhooks_dry_run_does_not_send() {
+        let client = CrtShClient::new(Config::default()).unwrap();
+        let webhooks = [webhook(WebhookKind::Slack, None)];
+        let deliveries = notify_webhooks(
+            &client,
+            &webhooks,
+            &[crt(1, "a.example.com")],
+            "example.com",
+            true,
+        );
+
+        assert!(
+            matches!(&deliveries[..], [(_, Delivery::DryRun(payload))] if payload.contains("a.example.com"))
+        );
+    }
+}